@@ -1 +1,3 @@
 glslc -O custom.frag -o custom.frag.spv
+glslc -O rgba_vertex.vert -o rgba_vertex.vert.spv
+glslc -O fullscreen.frag -o fullscreen.frag.spv
@@ -1,7 +1,12 @@
 use idek::{prelude::*, IndexBuffer, MultiPlatformCamera};
 
 fn main() -> Result<()> {
-    launch::<_, TriangleApp>(Settings::default().vr_if_any_args())
+    launch::<_, TriangleApp>(
+        Settings::default()
+            .name("Cube".into())
+            .show_fps(true)
+            .vr_if_any_args(),
+    )
 }
 
 struct TriangleApp {
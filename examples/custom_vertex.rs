@@ -0,0 +1,66 @@
+use idek::{prelude::*, AttributeFormat, VertexAttribute, VertexLayout};
+
+fn main() -> Result<()> {
+    launch::<_, CustomVertexApp>(Settings::default().vr_if_any_args())
+}
+
+/// A 2D vertex with an 8-bit RGBA color
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct RgbaVertex {
+    pos: [f32; 2],
+    color: [u8; 4],
+}
+
+unsafe impl bytemuck::Zeroable for RgbaVertex {}
+unsafe impl bytemuck::Pod for RgbaVertex {}
+
+impl VertexLayout for RgbaVertex {
+    fn attributes() -> Vec<VertexAttribute> {
+        vec![
+            VertexAttribute::new(0, AttributeFormat::Vec2, 0),
+            VertexAttribute::new(
+                1,
+                AttributeFormat::UNorm8x4,
+                std::mem::size_of::<[f32; 2]>(),
+            ),
+        ]
+    }
+}
+
+const TRIANGLE_MESH: [RgbaVertex; 3] = [
+    RgbaVertex {
+        pos: [0., 0.5],
+        color: [255, 0, 0, 255],
+    },
+    RgbaVertex {
+        pos: [0.5, -0.5],
+        color: [0, 0, 255, 255],
+    },
+    RgbaVertex {
+        pos: [-0.5, -0.5],
+        color: [0, 255, 0, 255],
+    },
+];
+
+struct CustomVertexApp {
+    verts: VertexBuffer,
+    shader: Shader,
+}
+
+impl App for CustomVertexApp {
+    fn init(ctx: &mut Context, _: &mut Platform, _: ()) -> Result<Self> {
+        let verts = ctx.vertices_of(&TRIANGLE_MESH, false)?;
+        let shader = ctx.shader_of::<RgbaVertex>(
+            include_bytes!("rgba_vertex.vert.spv"),
+            DEFAULT_FRAGMENT_SHADER,
+            Primitive::Triangles,
+            Default::default(),
+        )?;
+        Ok(Self { verts, shader })
+    }
+
+    fn frame(&mut self, _ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        Ok(vec![DrawCmd::new(self.verts).shader(self.shader)])
+    }
+}
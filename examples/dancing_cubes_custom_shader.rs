@@ -7,9 +7,12 @@ fn main() -> Result<()> {
     launch::<_, TriangleApp>(Settings::default().vr_if_any_args())
 }
 
+const N_CUBES: usize = 1000;
+
 struct TriangleApp {
     verts: VertexBuffer,
     indices: IndexBuffer,
+    instances: InstanceBuffer,
     camera: MultiPlatformCamera,
     shader: Shader,
 }
@@ -20,8 +23,9 @@ impl App for TriangleApp {
         Ok(Self {
             verts: ctx.vertices(&vertices, false)?,
             indices: ctx.indices(&indices, false)?,
+            instances: ctx.instances(&cube_instances(0.), true)?,
             shader: ctx.shader(
-                &DEFAULT_VERTEX_SHADER,
+                &DEFAULT_INSTANCED_VERTEX_SHADER,
                 &std::fs::read("examples/custom.frag.spv")?,
                 Primitive::Triangles,
             )?,
@@ -30,27 +34,13 @@ impl App for TriangleApp {
     }
 
     fn frame(&mut self, ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
-        let mut draw_cmds = vec![];
+        let time = ctx.start_time().elapsed().as_secs_f32();
+        ctx.update_instances(self.instances, &cube_instances(time))?;
 
-        let cube = DrawCmd::new(self.verts)
+        Ok(vec![DrawCmd::new(self.verts)
             .indices(self.indices)
-            .shader(self.shader);
-
-        let n_cubes = 1000;
-        for i in 0..n_cubes {
-            let mut i = i as f32 / n_cubes as f32;
-            i += ctx.start_time().elapsed().as_secs_f32() / 1000.;
-            i *= std::f32::consts::TAU;
-
-            let sz = 20.;
-            let x = i.cos() * sz;
-            let y = (i * 12. + 94.234).cos() * sz;
-            let z = (i * 4. + 9.234).cos() * sz;
-            let transform = Matrix4::new_translation(&Vector3::new(x, y, z));
-            draw_cmds.push(cube.transform(*transform.as_ref()));
-        }
-
-        Ok(draw_cmds)
+            .instances(self.instances)
+            .shader(self.shader)])
     }
 
     fn event(
@@ -67,6 +57,23 @@ impl App for TriangleApp {
     }
 }
 
+fn cube_instances(time: f32) -> Vec<Instance> {
+    (0..N_CUBES)
+        .map(|i| {
+            let mut i = i as f32 / N_CUBES as f32;
+            i += time / 1000.;
+            i *= std::f32::consts::TAU;
+
+            let sz = 20.;
+            let x = i.cos() * sz;
+            let y = (i * 12. + 94.234).cos() * sz;
+            let z = (i * 4. + 9.234).cos() * sz;
+            let transform = Matrix4::new_translation(&Vector3::new(x, y, z));
+            Instance::from_transform(*transform.as_ref())
+        })
+        .collect()
+}
+
 fn rainbow_cube() -> (Vec<Vertex>, Vec<u32>) {
     let vertices = vec![
         Vertex::new([-1.0, -1.0, -1.0], [0.0, 1.0, 1.0]),
@@ -0,0 +1,16 @@
+#version 450
+
+// Per-frame UBO
+layout(binding = 0) uniform PerFrame {
+    mat4 camera[2];
+    float time;
+};
+
+layout(location = 0) in vec2 frag_uv;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    vec2 st = frag_uv * 2. - 1.;
+    float rings = sin(length(st) * 20. - time * 4.) * 0.5 + 0.5;
+    out_color = vec4(frag_uv * rings, rings, 1.);
+}
@@ -0,0 +1,69 @@
+use idek::prelude::*;
+
+fn main() -> Result<()> {
+    launch::<_, GlslApp>(Settings::default().vr_if_any_args())
+}
+
+const TRIANGLE_MESH: [Vertex; 3] = [
+    Vertex {
+        pos: [0., 0.5, 0.],
+        color: [1., 0., 0.],
+    },
+    Vertex {
+        pos: [0.5, -0.5, 0.],
+        color: [0., 0., 1.],
+    },
+    Vertex {
+        pos: [-0.5, -0.5, 0.],
+        color: [0., 1., 0.],
+    },
+];
+
+const VERTEX_SHADER: &str = r#"
+#version 450
+#include <idek.glsl>
+
+layout(location = 0) in vec3 vert_pos;
+layout(location = 1) in vec3 vert_color;
+
+layout(location = 0) out vec3 frag_color;
+
+void main() {
+    gl_Position = camera[gl_ViewIndex] * model_mats[model_index] * vec4(vert_pos, 1.0);
+    frag_color = vert_color;
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+#version 450
+#define IDEK_UNIFORMS float speed;
+#include <idek.glsl>
+
+layout(location = 0) in vec3 frag_color;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(frag_color * (sin(time * speed) * 0.5 + 0.5), 1.0);
+}
+"#;
+
+struct GlslApp {
+    verts: VertexBuffer,
+    shader: Shader,
+}
+
+impl App for GlslApp {
+    fn init(ctx: &mut Context, _: &mut Platform, _: ()) -> Result<Self> {
+        Ok(Self {
+            verts: ctx.vertices(&TRIANGLE_MESH, false)?,
+            shader: ctx.shader_glsl(VERTEX_SHADER, FRAGMENT_SHADER, Primitive::Triangles)?,
+        })
+    }
+
+    fn frame(&mut self, _ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        let speed: f32 = 2.;
+        Ok(vec![DrawCmd::new(self.verts)
+            .shader(self.shader)
+            .uniforms_pod(&speed)])
+    }
+}
@@ -0,0 +1,48 @@
+use idek::prelude::*;
+
+fn main() -> Result<()> {
+    launch::<_, HotReloadApp>(Settings::default().vr_if_any_args())
+}
+
+const TRIANGLE_MESH: [Vertex; 3] = [
+    Vertex {
+        pos: [0., 0.5, 0.],
+        color: [1., 0., 0.],
+    },
+    Vertex {
+        pos: [0.5, -0.5, 0.],
+        color: [0., 0., 1.],
+    },
+    Vertex {
+        pos: [-0.5, -0.5, 0.],
+        color: [0., 1., 0.],
+    },
+];
+
+struct HotReloadApp {
+    verts: VertexBuffer,
+    shader: Shader,
+}
+
+impl App for HotReloadApp {
+    fn init(ctx: &mut Context, _: &mut Platform, _: ()) -> Result<Self> {
+        let verts = ctx.vertices(&TRIANGLE_MESH, false)?;
+
+        // Edit examples/custom.frag while this is running to see the changes
+        let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+        let shader = ctx.shader_from_files(
+            root.join("src/shaders/unlit.vert"),
+            root.join("examples/custom.frag"),
+            Primitive::Triangles,
+        )?;
+
+        Ok(Self { verts, shader })
+    }
+
+    fn frame(&mut self, ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        for error in ctx.take_shader_reload_errors() {
+            eprintln!("{:#}", error);
+        }
+        Ok(vec![DrawCmd::new(self.verts).shader(self.shader)])
+    }
+}
@@ -0,0 +1,60 @@
+use idek::prelude::*;
+
+fn main() -> Result<()> {
+    launch::<_, ProceduralApp>(Settings::default().vr_if_any_args())
+}
+
+const N_SEGMENTS: u32 = 64;
+
+// Builds a disc out of triangles, using only the vertex index
+const VERTEX_SHADER: &str = r#"
+#version 450
+#include <idek.glsl>
+
+layout(location = 0) out vec3 frag_color;
+
+const uint N_SEGMENTS = 64;
+const float TAU = 6.28318530718;
+
+void main() {
+    uint segment = uint(gl_VertexIndex) / 3;
+    uint corner = uint(gl_VertexIndex) % 3;
+
+    vec2 pos = vec2(0.);
+    if (corner != 0) {
+        float angle = TAU * float(segment + corner - 1) / float(N_SEGMENTS);
+        pos = vec2(cos(angle), sin(angle)) * 0.5;
+    }
+
+    gl_Position = camera[gl_ViewIndex] * model_mats[model_index] * vec4(pos, 0., 1.);
+    float hue = TAU * float(segment) / float(N_SEGMENTS) + time;
+    frag_color = vec3(sin(hue), sin(hue + 2.1), sin(hue + 4.2)) * 0.5 + 0.5;
+}
+"#;
+
+const FRAGMENT_SHADER: &str = r#"
+#version 450
+
+layout(location = 0) in vec3 frag_color;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = vec4(frag_color, 1.);
+}
+"#;
+
+struct ProceduralApp {
+    shader: Shader,
+}
+
+impl App for ProceduralApp {
+    fn init(ctx: &mut Context, _: &mut Platform, _: ()) -> Result<Self> {
+        let shader =
+            ctx.procedural_shader_glsl(VERTEX_SHADER, FRAGMENT_SHADER, Primitive::Triangles)?;
+        Ok(Self { shader })
+    }
+
+    fn frame(&mut self, _ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        Ok(vec![DrawCmd::procedural(N_SEGMENTS * 3, self.shader)])
+    }
+}
@@ -0,0 +1,92 @@
+use idek::{prelude::*, StorageBuffer};
+
+fn main() -> Result<()> {
+    launch::<_, StorageBufferApp>(Settings::default().vr_if_any_args())
+}
+
+const TRIANGLE_MESH: [Vertex; 3] = [
+    Vertex {
+        pos: [0., 0.5, 0.],
+        color: [0., 0., 0.],
+    },
+    Vertex {
+        pos: [0.5, -0.5, 0.],
+        color: [1., 0., 0.],
+    },
+    Vertex {
+        pos: [-0.5, -0.5, 0.],
+        color: [0.5, 0., 0.],
+    },
+];
+
+const VERTEX_SHADER: &str = r#"
+#version 450
+#include <idek.glsl>
+
+layout(location = 0) in vec3 vert_pos;
+layout(location = 1) in vec3 vert_color;
+
+layout(location = 0) out float frag_value;
+
+void main() {
+    gl_Position = camera[gl_ViewIndex] * model_mats[model_index] * vec4(vert_pos, 1.0);
+    frag_value = vert_color.r;
+}
+"#;
+
+// Looks up the interpolated value in a colormap stored in slot 0
+const FRAGMENT_SHADER: &str = r#"
+#version 450
+
+layout(set = 2, binding = 0) readonly buffer Colormap {
+    vec4 colors[];
+};
+
+layout(location = 0) in float frag_value;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    int idx = int(clamp(frag_value, 0.0, 1.0) * float(colors.length() - 1));
+    out_color = colors[idx];
+}
+"#;
+
+const COLORMAP_SIZE: usize = 64;
+
+struct StorageBufferApp {
+    verts: VertexBuffer,
+    colormap: StorageBuffer,
+    shader: Shader,
+}
+
+impl App for StorageBufferApp {
+    fn init(ctx: &mut Context, _: &mut Platform, _: ()) -> Result<Self> {
+        let verts = ctx.vertices(&TRIANGLE_MESH, false)?;
+        let colormap = ctx.storage_buffer(bytemuck::cast_slice(&colormap(0.)), true)?;
+        let shader = ctx.shader_glsl(VERTEX_SHADER, FRAGMENT_SHADER, Primitive::Triangles)?;
+        ctx.attach_storage_buffer(shader, 0, colormap)?;
+
+        Ok(Self {
+            verts,
+            colormap,
+            shader,
+        })
+    }
+
+    fn frame(&mut self, ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        let time = ctx.start_time().elapsed().as_secs_f32();
+        ctx.update_storage_buffer(self.colormap, bytemuck::cast_slice(&colormap(time)))?;
+        Ok(vec![DrawCmd::new(self.verts).shader(self.shader)])
+    }
+}
+
+/// Rainbow colormap which cycles over time
+fn colormap(time: f32) -> Vec<[f32; 4]> {
+    (0..COLORMAP_SIZE)
+        .map(|i| {
+            let t = i as f32 / COLORMAP_SIZE as f32 * std::f32::consts::TAU + time;
+            let channel = |phase: f32| (t + phase).sin() * 0.5 + 0.5;
+            [channel(0.), channel(2.1), channel(4.2), 1.]
+        })
+        .collect()
+}
@@ -0,0 +1,70 @@
+use idek::{
+    nalgebra::{Matrix4, Vector3},
+    prelude::*,
+    Background, Mesh, MeshBuilder, MultiPlatformCamera,
+};
+
+fn main() -> Result<()> {
+    let background = Background::Gradient {
+        top: [0.1, 0.1, 0.2, 1.],
+        bottom: [0.6, 0.6, 0.7, 1.],
+    };
+    launch::<_, SubMeshApp>(Settings::default().background(background).vr_if_any_args())
+}
+
+struct SubMeshApp {
+    mesh: Mesh,
+    camera: MultiPlatformCamera,
+}
+
+impl App for SubMeshApp {
+    fn init(ctx: &mut Context, platform: &mut Platform, _: ()) -> Result<Self> {
+        // Pack a square and a triangle into the same pair of buffers
+        let mut builder = MeshBuilder::new();
+        builder.push(
+            &[
+                Vertex::new([-0.5, -0.5, 0.], [1., 0., 0.]),
+                Vertex::new([0.5, -0.5, 0.], [0., 1., 0.]),
+                Vertex::new([0.5, 0.5, 0.], [0., 0., 1.]),
+                Vertex::new([-0.5, 0.5, 0.], [1., 1., 0.]),
+            ],
+            &[3, 1, 0, 3, 2, 1, 0, 1, 3, 1, 2, 3],
+        );
+        builder.push(
+            &[
+                Vertex::new([0., 0.5, 0.], [1., 0., 1.]),
+                Vertex::new([0.5, -0.5, 0.], [0., 1., 1.]),
+                Vertex::new([-0.5, -0.5, 0.], [1., 1., 1.]),
+            ],
+            &[0, 1, 2, 2, 1, 0],
+        );
+
+        Ok(Self {
+            mesh: builder.upload(ctx, false)?,
+            camera: MultiPlatformCamera::new(platform),
+        })
+    }
+
+    fn frame(&mut self, _ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        Ok((0..self.mesh.submeshes.len())
+            .map(|i| {
+                let x = i as f32 * 1.5 - 0.75;
+                let transform = Matrix4::new_translation(&Vector3::new(x, 0., 0.));
+                self.mesh.draw(i).transform(*transform.as_ref())
+            })
+            .collect())
+    }
+
+    fn event(
+        &mut self,
+        ctx: &mut Context,
+        platform: &mut Platform,
+        mut event: Event,
+    ) -> Result<()> {
+        if self.camera.handle_event(&mut event) {
+            ctx.set_camera_prefix(self.camera.get_prefix())
+        }
+        idek::close_when_asked(platform, &event);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use idek::{prelude::*, Filter, IndexBuffer, SamplerSettings};
+
+fn main() -> Result<()> {
+    launch::<_, TextureApp>(Settings::default().vr_if_any_args())
+}
+
+// The default textured shader uses the red and green channels as UV coordinates
+const QUAD_VERTS: [Vertex; 4] = [
+    Vertex {
+        pos: [-1., -1., 0.],
+        color: [0., 0., 0.],
+    },
+    Vertex {
+        pos: [1., -1., 0.],
+        color: [4., 0., 0.],
+    },
+    Vertex {
+        pos: [1., 1., 0.],
+        color: [4., 4., 0.],
+    },
+    Vertex {
+        pos: [-1., 1., 0.],
+        color: [0., 4., 0.],
+    },
+];
+
+const QUAD_INDICES: [u32; 12] = [
+    // Facing toward the camera
+    3, 1, 0, 3, 2, 1, // Facing away
+    0, 1, 3, 1, 2, 3,
+];
+
+const TEXTURE_WIDTH: usize = 8;
+
+struct TextureApp {
+    verts: VertexBuffer,
+    indices: IndexBuffer,
+    texture: Texture,
+    shader: Shader,
+}
+
+impl App for TextureApp {
+    fn init(ctx: &mut Context, _: &mut Platform, _: ()) -> Result<Self> {
+        let verts = ctx.vertices(&QUAD_VERTS, false)?;
+        let indices = ctx.indices(&QUAD_INDICES, false)?;
+
+        let texture = ctx.texture_with(
+            &checkerboard(0.),
+            TEXTURE_WIDTH,
+            true,
+            SamplerSettings::default().filter(Filter::Nearest),
+        )?;
+
+        let shader = ctx.shader(
+            DEFAULT_VERTEX_SHADER,
+            DEFAULT_TEXTURED_FRAGMENT_SHADER,
+            Primitive::Triangles,
+        )?;
+
+        Ok(Self {
+            verts,
+            indices,
+            texture,
+            shader,
+        })
+    }
+
+    fn frame(&mut self, ctx: &mut Context, _: &mut Platform) -> Result<Vec<DrawCmd>> {
+        let time = ctx.start_time().elapsed().as_secs_f32();
+        ctx.update_texture(self.texture, &checkerboard(time))?;
+
+        Ok(vec![DrawCmd::new(self.verts)
+            .indices(self.indices)
+            .texture(self.texture)
+            .shader(self.shader)])
+    }
+}
+
+/// Checkerboard pattern which cycles in color over time
+fn checkerboard(time: f32) -> Vec<u8> {
+    let mut data = Vec::with_capacity(TEXTURE_WIDTH * TEXTURE_WIDTH * 4);
+    for y in 0..TEXTURE_WIDTH {
+        for x in 0..TEXTURE_WIDTH {
+            let color = if (x + y) % 2 == 0 {
+                [
+                    ((time.sin() + 1.) * 127.) as u8,
+                    ((time.cos() + 1.) * 127.) as u8,
+                    255,
+                    255,
+                ]
+            } else {
+                [0, 0, 0, 255]
+            };
+            data.extend_from_slice(&color);
+        }
+    }
+    data
+}
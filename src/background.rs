@@ -0,0 +1,14 @@
+/// What is drawn behind everything else each frame
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Background {
+    /// Clear to a single RGBA color
+    Color([f32; 4]),
+    /// Blend vertically between two RGBA colors
+    Gradient { top: [f32; 4], bottom: [f32; 4] },
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Color([0., 0., 0., 1.])
+    }
+}
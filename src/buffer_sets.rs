@@ -0,0 +1,206 @@
+use crate::{StorageBuffer, UniformBuffer};
+use anyhow::Result;
+use std::collections::HashMap;
+use watertender::defaults::FRAMES_IN_FLIGHT;
+use watertender::prelude::*;
+
+/// Number of storage buffer slots, bound at `set = 2, binding = slot`
+pub const STORAGE_BUFFER_SLOTS: usize = 4;
+
+/// Number of uniform buffer slots, bound at `set = 2, binding = STORAGE_BUFFER_SLOTS + slot`
+pub const UNIFORM_BUFFER_SLOTS: usize = 4;
+
+/// Descriptor set index of user buffers
+pub const BUFFER_SET: u32 = 2;
+
+/// Number of descriptor sets in each pool. More pools are created as needed.
+const SETS_PER_POOL: u32 = 64;
+
+/// User buffers bound to each slot
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct BufferBindings {
+    pub storage: [Option<StorageBuffer>; STORAGE_BUFFER_SLOTS],
+    pub uniform: [Option<UniformBuffer>; UNIFORM_BUFFER_SLOTS],
+}
+
+impl BufferBindings {
+    /// True if no buffers are bound
+    pub fn is_empty(&self) -> bool {
+        self.storage.iter().all(Option::is_none) && self.uniform.iter().all(Option::is_none)
+    }
+
+    /// Bindings of `self`, with any slots bound in `other` replaced
+    pub fn overridden_by(mut self, other: &BufferBindings) -> Self {
+        for (slot, buffer) in self.storage.iter_mut().zip(other.storage.iter()) {
+            if buffer.is_some() {
+                *slot = *buffer;
+            }
+        }
+        for (slot, buffer) in self.uniform.iter_mut().zip(other.uniform.iter()) {
+            if buffer.is_some() {
+                *slot = *buffer;
+            }
+        }
+        self
+    }
+}
+
+/// Allocates descriptor sets for combinations of user buffers, anew each frame
+pub struct BufferSets {
+    layout: vk::DescriptorSetLayout,
+    /// Descriptor pools for each frame in flight
+    pools: Vec<Vec<vk::DescriptorPool>>,
+    /// Index of the pool currently allocated from, and the number of sets allocated from it, for
+    /// each frame
+    current: Vec<(usize, u32)>,
+    /// Sets already written this frame
+    cache: Vec<HashMap<BufferBindings, vk::DescriptorSet>>,
+}
+
+impl BufferSets {
+    pub fn new(core: &Core) -> Result<Self> {
+        let bindings: Vec<_> = (0..STORAGE_BUFFER_SLOTS + UNIFORM_BUFFER_SLOTS)
+            .map(|slot| {
+                vk::DescriptorSetLayoutBindingBuilder::new()
+                    .binding(slot as u32)
+                    .descriptor_type(descriptor_type(slot))
+                    .descriptor_count(1)
+                    .stage_flags(vk::ShaderStageFlags::ALL_GRAPHICS)
+            })
+            .collect();
+
+        let create_info = vk::DescriptorSetLayoutCreateInfoBuilder::new().bindings(&bindings);
+        let layout = unsafe {
+            core.device
+                .create_descriptor_set_layout(&create_info, None, None)
+        }
+        .result()?;
+
+        let mut instance = Self {
+            layout,
+            pools: vec![vec![]; FRAMES_IN_FLIGHT],
+            current: vec![(0, 0); FRAMES_IN_FLIGHT],
+            cache: vec![HashMap::new(); FRAMES_IN_FLIGHT],
+        };
+
+        for frame in 0..FRAMES_IN_FLIGHT {
+            instance.add_pool(core, frame)?;
+        }
+
+        Ok(instance)
+    }
+
+    pub fn layout(&self) -> vk::DescriptorSetLayout {
+        self.layout
+    }
+
+    /// Free all sets allocated for the given frame. Must be called after the frame's fence has
+    /// been waited on.
+    pub fn reset(&mut self, core: &Core, frame: usize) -> Result<()> {
+        for &pool in &self.pools[frame] {
+            unsafe { core.device.reset_descriptor_pool(pool, None) }.result()?;
+        }
+        self.current[frame] = (0, 0);
+        self.cache[frame].clear();
+        Ok(())
+    }
+
+    /// Get a descriptor set for the given bindings this frame, writing `infos` (binding index,
+    /// buffer and size in bytes of each bound buffer) into it if it is not already cached
+    pub fn get(
+        &mut self,
+        core: &Core,
+        frame: usize,
+        bindings: &BufferBindings,
+        infos: &[(u32, vk::Buffer, u64)],
+    ) -> Result<vk::DescriptorSet> {
+        if let Some(&set) = self.cache[frame].get(bindings) {
+            return Ok(set);
+        }
+
+        // Move on to the next pool once the current one is full, creating it if needed
+        let (mut pool_idx, mut allocated) = self.current[frame];
+        if allocated == SETS_PER_POOL {
+            pool_idx += 1;
+            allocated = 0;
+            if pool_idx == self.pools[frame].len() {
+                self.add_pool(core, frame)?;
+            }
+        }
+
+        let pool = self.pools[frame][pool_idx];
+        let layouts = [self.layout];
+        let allocate_info = vk::DescriptorSetAllocateInfoBuilder::new()
+            .descriptor_pool(pool)
+            .set_layouts(&layouts);
+        let set = unsafe { core.device.allocate_descriptor_sets(&allocate_info) }.result()?[0];
+        self.current[frame] = (pool_idx, allocated + 1);
+
+        let buffer_infos: Vec<[vk::DescriptorBufferInfoBuilder; 1]> = infos
+            .iter()
+            .map(|&(_, buffer, size)| {
+                [vk::DescriptorBufferInfoBuilder::new()
+                    .buffer(buffer)
+                    .offset(0)
+                    .range(size.max(1))]
+            })
+            .collect();
+
+        let writes: Vec<_> = infos
+            .iter()
+            .zip(&buffer_infos)
+            .map(|(&(binding, _, _), info)| {
+                vk::WriteDescriptorSetBuilder::new()
+                    .buffer_info(info)
+                    .descriptor_type(descriptor_type(binding as usize))
+                    .dst_set(set)
+                    .dst_binding(binding)
+                    .dst_array_element(0)
+            })
+            .collect();
+
+        unsafe {
+            core.device.update_descriptor_sets(&writes, &[]);
+        }
+
+        self.cache[frame].insert(*bindings, set);
+        Ok(set)
+    }
+
+    fn add_pool(&mut self, core: &Core, frame: usize) -> Result<()> {
+        let pool_sizes = [
+            vk::DescriptorPoolSizeBuilder::new()
+                ._type(vk::DescriptorType::STORAGE_BUFFER)
+                .descriptor_count(SETS_PER_POOL * STORAGE_BUFFER_SLOTS as u32),
+            vk::DescriptorPoolSizeBuilder::new()
+                ._type(vk::DescriptorType::UNIFORM_BUFFER)
+                .descriptor_count(SETS_PER_POOL * UNIFORM_BUFFER_SLOTS as u32),
+        ];
+
+        let create_info = vk::DescriptorPoolCreateInfoBuilder::new()
+            .pool_sizes(&pool_sizes)
+            .max_sets(SETS_PER_POOL);
+
+        let pool =
+            unsafe { core.device.create_descriptor_pool(&create_info, None, None) }.result()?;
+        self.pools[frame].push(pool);
+        Ok(())
+    }
+
+    /// Destroy the pools and layout. Must only be called once the device is idle.
+    pub unsafe fn destroy(&mut self, core: &Core) {
+        for pool in self.pools.drain(..).flatten() {
+            core.device.destroy_descriptor_pool(Some(pool), None);
+        }
+        core.device
+            .destroy_descriptor_set_layout(Some(self.layout), None);
+    }
+}
+
+/// Descriptor type of the given binding
+fn descriptor_type(binding: usize) -> vk::DescriptorType {
+    match binding < STORAGE_BUFFER_SLOTS {
+        true => vk::DescriptorType::STORAGE_BUFFER,
+        false => vk::DescriptorType::UNIFORM_BUFFER,
+    }
+}
@@ -0,0 +1,144 @@
+use std::ffi::CStr;
+use watertender::prelude::*;
+
+/// Kind of physical device
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeviceType {
+    Discrete,
+    Integrated,
+    Virtual,
+    /// A software renderer such as lavapipe or SwiftShader
+    Software,
+    Other,
+}
+
+/// Limits of the device which are relevant to idek apps
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceLimits {
+    /// Largest width or height of a texture
+    pub max_texture_size: u32,
+    /// Largest range of a storage buffer binding, in bytes
+    pub max_storage_buffer_range: u32,
+    /// Largest range of a uniform buffer binding, in bytes
+    pub max_uniform_buffer_range: u32,
+    /// Size of the push constant block, in bytes. Limits `DrawCmd::uniforms`
+    pub max_push_constants_size: u32,
+    /// Highest MSAA sample count supported for both color and depth
+    pub max_msaa_samples: u8,
+    /// Sample counts supported for both color and depth, as `vk::SampleCountFlags` bits
+    pub(crate) msaa_sample_counts: u32,
+}
+
+impl DeviceLimits {
+    /// The highest sample count supported for both color and depth attachments, up to
+    /// `requested`, which must be a power of two
+    pub(crate) fn msaa_samples_up_to(&self, requested: u8) -> u8 {
+        // Sample count flags are bitmasks with the same value as the count itself
+        let mut samples = requested;
+        while samples > 1 && self.msaa_sample_counts & samples as u32 == 0 {
+            samples /= 2;
+        }
+        samples
+    }
+}
+
+/// Optional features of a device. These are the features the physical device supports, which
+/// are not necessarily enabled on the logical device idek renders with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeviceFeatures {
+    /// Required for `PolygonMode::Line` and `PolygonMode::Point`, which additionally need the
+    /// feature to be enabled
+    pub fill_mode_non_solid: bool,
+    /// Line widths other than 1.0
+    pub wide_lines: bool,
+    pub sampler_anisotropy: bool,
+}
+
+/// The physical device in use, see `Context::device_info`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub device_type: DeviceType,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    /// Raw driver version, encoded in a vendor-specific format. See `driver_version_string`
+    pub driver_version: u32,
+    /// Vulkan version supported by the device, as (major, minor, patch)
+    pub api_version: (u32, u32, u32),
+    pub limits: DeviceLimits,
+    /// Features the device supports, which are not necessarily enabled
+    pub supported_features: DeviceFeatures,
+}
+
+impl DeviceInfo {
+    /// The driver version, decoded according to the conventions of known vendors
+    pub fn driver_version_string(&self) -> String {
+        let v = self.driver_version;
+        match self.vendor_id {
+            // NVIDIA: 10 bits major, 8 bits minor, 8 bits secondary, 6 bits tertiary
+            0x10de => format!(
+                "{}.{}.{}.{}",
+                v >> 22,
+                (v >> 14) & 0xff,
+                (v >> 6) & 0xff,
+                v & 0x3f
+            ),
+            // Intel on Windows: 18 bits major, 14 bits minor
+            0x8086 if cfg!(windows) => format!("{}.{}", v >> 14, v & 0x3fff),
+            // Everyone else follows the Vulkan version encoding
+            _ => format!("{}.{}.{}", v >> 22, (v >> 12) & 0x3ff, v & 0xfff),
+        }
+    }
+
+    pub(crate) fn query(core: &Core) -> Self {
+        let (properties, features) = unsafe {
+            (
+                core.instance
+                    .get_physical_device_properties(core.physical_device),
+                core.instance
+                    .get_physical_device_features(core.physical_device),
+            )
+        };
+
+        let name = unsafe { CStr::from_ptr(properties.device_name.as_ptr()) }
+            .to_string_lossy()
+            .into_owned();
+
+        let device_type = match properties.device_type {
+            vk::PhysicalDeviceType::DISCRETE_GPU => DeviceType::Discrete,
+            vk::PhysicalDeviceType::INTEGRATED_GPU => DeviceType::Integrated,
+            vk::PhysicalDeviceType::VIRTUAL_GPU => DeviceType::Virtual,
+            vk::PhysicalDeviceType::CPU => DeviceType::Software,
+            _ => DeviceType::Other,
+        };
+
+        let version = properties.api_version;
+        let limits = properties.limits;
+
+        let mut device_limits = DeviceLimits {
+            max_texture_size: limits.max_image_dimension2_d,
+            max_storage_buffer_range: limits.max_storage_buffer_range,
+            max_uniform_buffer_range: limits.max_uniform_buffer_range,
+            max_push_constants_size: limits.max_push_constants_size,
+            max_msaa_samples: 1,
+            msaa_sample_counts: limits.framebuffer_color_sample_counts.bits()
+                & limits.framebuffer_depth_sample_counts.bits(),
+        };
+        device_limits.max_msaa_samples = device_limits.msaa_samples_up_to(16);
+
+        Self {
+            name,
+            device_type,
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            driver_version: properties.driver_version,
+            api_version: (version >> 22, (version >> 12) & 0x3ff, version & 0xfff),
+            limits: device_limits,
+            supported_features: DeviceFeatures {
+                fill_mode_non_solid: features.fill_mode_non_solid != 0,
+                wide_lines: features.wide_lines != 0,
+                sampler_anisotropy: features.sampler_anisotropy != 0,
+            },
+        }
+    }
+}
@@ -1,44 +1,129 @@
 use crate::*;
 
+/// Offset of the user data in a draw's push constants, in bytes. The transform index is padded to
+/// 16 bytes, so that user data of any type starts at its natural alignment.
+pub(crate) const UNIFORMS_OFFSET: usize = 16;
+
+/// Maximum size of the user data in a draw's push constants, in bytes. The first 16 bytes of the
+/// 128 guaranteed by Vulkan are reserved for the transform index.
+pub const MAX_UNIFORMS_SIZE: usize = 128 - UNIFORMS_OFFSET;
+
+/// User-defined push constant data for a single draw, placed after the transform index
+#[derive(Copy, Clone)]
+pub struct Uniforms {
+    data: [u8; MAX_UNIFORMS_SIZE],
+    len: usize,
+}
+
+impl Uniforms {
+    /// Copy the given bytes. Data beyond `MAX_UNIFORMS_SIZE` is dropped, and reported as an error
+    /// when the draw is submitted.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut data = [0; MAX_UNIFORMS_SIZE];
+        let copied = bytes.len().min(MAX_UNIFORMS_SIZE);
+        data[..copied].copy_from_slice(&bytes[..copied]);
+        Self {
+            data,
+            len: bytes.len(),
+        }
+    }
+
+    /// Size of the data supplied, in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The data, zero-padded to a multiple of 4 bytes as required for push constants
+    pub(crate) fn padded_bytes(&self) -> &[u8] {
+        let padded = (self.len + 3) / 4 * 4;
+        &self.data[..padded.min(MAX_UNIFORMS_SIZE)]
+    }
+}
+
 /// A draw command, flexibly represents a number of different drawing
 #[derive(Copy, Clone)]
 pub struct DrawCmd {
-    pub vertices: VertexBuffer,
-    //pub instances: Option<InstanceBuffer>,
+    pub vertices: Option<VertexBuffer>,
+    pub instances: Option<InstanceBuffer>,
     pub indices: Option<IndexBuffer>,
-    //pub texture: Option<Texture>,
+    pub texture: Option<Texture>,
     pub shader: Option<Shader>,
     pub transform: Option<Transform>,
+    pub uniforms: Option<Uniforms>,
+    /// User buffers, in addition to any attached to the shader
+    pub buffers: BufferBindings,
     pub limit: Option<u32>,
+    /// First index (if indices have been defined) or vertex to draw
+    pub first: u32,
+    /// Added to each index before reading a vertex. Only applies to indexed draws
+    pub base_vertex: i32,
+    /// Set when a buffer was bound to a slot which does not exist, reported when the draw is
+    /// submitted
+    pub(crate) slot_error: Option<Error>,
 }
 
 impl DrawCmd {
     pub fn new(vertices: VertexBuffer) -> Self {
         Self {
-            vertices,
-            //instances: None,
+            vertices: Some(vertices),
+            ..Self::new_empty()
+        }
+    }
+
+    /// Draw `count` vertices without a vertex buffer. The shader must not read vertex
+    /// attributes; it may generate geometry from `gl_VertexIndex`, or read it from storage
+    /// buffers. Such shaders may be created with `Context::procedural_shader`. Draws with a
+    /// shader which reads vertex attributes are skipped with `Error::LayoutMismatch`.
+    pub fn procedural(count: u32, shader: Shader) -> Self {
+        Self {
+            shader: Some(shader),
+            limit: Some(count),
+            ..Self::new_empty()
+        }
+    }
+
+    /// Draw a shader created with `Context::fullscreen_shader` over the whole screen
+    pub fn fullscreen(shader: Shader) -> Self {
+        Self::procedural(3, shader)
+    }
+
+    /// A draw with no resources bound
+    fn new_empty() -> Self {
+        Self {
+            vertices: None,
+            instances: None,
             indices: None,
-            //texture: None,
+            texture: None,
             shader: None,
             transform: None,
+            uniforms: None,
+            buffers: BufferBindings::default(),
             limit: None,
+            first: 0,
+            base_vertex: 0,
+            slot_error: None,
         }
     }
 
-    /*pub fn instances(mut self, instances: InstanceBuffer) -> Self {
+    /// Draw once for each instance in the buffer
+    pub fn instances(mut self, instances: InstanceBuffer) -> Self {
         self.instances = Some(instances);
         self
-    }*/
+    }
 
     pub fn indices(mut self, indices: IndexBuffer) -> Self {
         self.indices = Some(indices);
         self
     }
 
-    /*pub fn texture(mut self, texture: Texture) -> Self {
+    pub fn texture(mut self, texture: Texture) -> Self {
         self.texture = Some(texture);
         self
-    }*/
+    }
 
     pub fn shader(mut self, shader: Shader) -> Self {
         self.shader = Some(shader);
@@ -50,6 +135,53 @@ impl DrawCmd {
         self
     }
 
+    /// Supply up to `MAX_UNIFORMS_SIZE` bytes of push constant data to the vertex and fragment
+    /// shaders, declared with `IDEK_UNIFORMS` in `idek.glsl`. The data starts 16 bytes into the
+    /// push constant block, and must follow its std430 layout.
+    pub fn uniforms(mut self, data: &[u8]) -> Self {
+        self.uniforms = Some(Uniforms::new(data));
+        self
+    }
+
+    /// Same as `uniforms`, but for a typed value
+    pub fn uniforms_pod<T: bytemuck::Pod>(self, value: &T) -> Self {
+        self.uniforms(bytemuck::bytes_of(value))
+    }
+
+    /// Bind a storage buffer at `set = 2, binding = slot`, replacing any buffer attached to the
+    /// shader in that slot. If `slot` is not less than `STORAGE_BUFFER_SLOTS`, the draw is
+    /// skipped with `Error::InvalidSlot`.
+    pub fn storage_buffer(mut self, slot: usize, buffer: StorageBuffer) -> Self {
+        match self.buffers.storage.get_mut(slot) {
+            Some(binding) => *binding = Some(buffer),
+            None => {
+                self.slot_error = Some(Error::InvalidSlot {
+                    kind: ResourceKind::StorageBuffer,
+                    slot,
+                    slots: STORAGE_BUFFER_SLOTS,
+                })
+            }
+        }
+        self
+    }
+
+    /// Bind a uniform buffer at `set = 2, binding = STORAGE_BUFFER_SLOTS + slot`, replacing any
+    /// buffer attached to the shader in that slot. If `slot` is not less than
+    /// `UNIFORM_BUFFER_SLOTS`, the draw is skipped with `Error::InvalidSlot`.
+    pub fn uniform_buffer(mut self, slot: usize, buffer: UniformBuffer) -> Self {
+        match self.buffers.uniform.get_mut(slot) {
+            Some(binding) => *binding = Some(buffer),
+            None => {
+                self.slot_error = Some(Error::InvalidSlot {
+                    kind: ResourceKind::UniformBuffer,
+                    slot,
+                    slots: UNIFORM_BUFFER_SLOTS,
+                })
+            }
+        }
+        self
+    }
+
     /// If vertices have been defined:              Limit vertex drawing to this number
     /// If indices and vertices have been defined:  Limit indexes used to this number
     /// If neither vertices nor indices:            Draw this many vertices
@@ -57,4 +189,72 @@ impl DrawCmd {
         self.limit = Some(limit);
         self
     }
+
+    /// Draw `count` indices (if indices have been defined) or vertices, starting from `first`
+    pub fn range(mut self, first: u32, count: u32) -> Self {
+        self.first = first;
+        self.limit = Some(count);
+        self
+    }
+
+    /// Offset added to each index before reading a vertex. Only applies to indexed draws
+    pub fn base_vertex(mut self, base_vertex: i32) -> Self {
+        self.base_vertex = base_vertex;
+        self
+    }
+
+    /// Draw only the given part of the bound vertex and index buffers
+    pub fn submesh(self, submesh: SubMesh) -> Self {
+        self.range(submesh.first_index, submesh.index_count)
+            .base_vertex(submesh.base_vertex)
+    }
+
+    /// Number of indices or vertices to draw, given the number `available` in the buffer being
+    /// drawn from, if any
+    pub(crate) fn count(&self, available: Option<u32>) -> u32 {
+        match available {
+            Some(available) => {
+                let remaining = available.saturating_sub(self.first);
+                self.limit.map_or(remaining, |limit| limit.min(remaining))
+            }
+            None => self.limit.unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_whole_buffer() {
+        let cmd = DrawCmd::new(VertexBuffer::default());
+        assert_eq!(cmd.count(Some(6)), 6);
+    }
+
+    #[test]
+    fn count_limited() {
+        let cmd = DrawCmd::new(VertexBuffer::default()).limit(4);
+        assert_eq!(cmd.count(Some(6)), 4);
+        assert_eq!(cmd.count(Some(3)), 3);
+    }
+
+    #[test]
+    fn count_range_saturates() {
+        let cmd = DrawCmd::new(VertexBuffer::default()).range(4, 10);
+        assert_eq!(cmd.count(Some(6)), 2);
+        assert_eq!(cmd.count(Some(4)), 0);
+        assert_eq!(cmd.count(Some(2)), 0);
+    }
+
+    #[test]
+    fn count_without_buffer() {
+        assert_eq!(DrawCmd::procedural(3, Shader::default()).count(None), 3);
+        assert_eq!(
+            DrawCmd::procedural(3, Shader::default())
+                .range(1, 2)
+                .count(None),
+            2
+        );
+    }
 }
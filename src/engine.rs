@@ -1,9 +1,31 @@
-use crate::Transform;
-use crate::{App, DrawCmd, IndexBuffer, Settings, Shader, VertexBuffer};
-use crate::{DEFAULT_FRAGMENT_SHADER, DEFAULT_VERTEX_SHADER};
+use crate::background::Background;
+use crate::buffer_sets::{BufferBindings, BufferSets, BUFFER_SET};
+use crate::device_info::DeviceInfo;
+use crate::draw_cmd::UNIFORMS_OFFSET;
+#[cfg(feature = "shaderc")]
+use crate::glsl::compile_glsl;
+use crate::index::{index_type_name, Index};
+use crate::pipeline::{create_pipeline, PipelineDesc, PipelineTarget};
+use crate::pipeline::{INSTANCE_BINDING, VERTEX_BINDING};
+use crate::shader_files::{load_shader_file, Stage};
+#[cfg(feature = "hot-reload")]
+use crate::shader_files::{ShaderWatcher, TrackedShader};
+use crate::vertex_layout::{LayoutId, VertexLayoutInfo};
+use crate::window_title::WindowTitle;
+use crate::winit::dpi::{PhysicalPosition, PhysicalSize};
+use crate::winit::window::Fullscreen;
+use crate::{App, DrawCmd, IndexBuffer, SamplerSettings, Settings, Shader, Texture, VertexBuffer};
+use crate::{CullMode, PolygonMode, ShaderSettings, VertexLayout};
+use crate::{DrawError, Error, ResourceKind};
+use crate::{Instance, InstanceBuffer, Transform, MAX_UNIFORMS_SIZE};
+use crate::{StorageBuffer, UniformBuffer, STORAGE_BUFFER_SLOTS, UNIFORM_BUFFER_SLOTS};
+use crate::{DEFAULT_FRAGMENT_SHADER, DEFAULT_INSTANCED_VERTEX_SHADER, DEFAULT_VERTEX_SHADER};
+use crate::{FULLSCREEN_VERTEX_SHADER, GRADIENT_FRAGMENT_SHADER};
 use anyhow::{ensure, Result};
-use slotmap::SlotMap;
+use slotmap::{SecondaryMap, SlotMap};
 use std::marker::PhantomData;
+use std::ops::Range;
+use std::path::Path;
 use std::time::Instant;
 use watertender::defaults::FRAMES_IN_FLIGHT;
 use watertender::{
@@ -21,8 +43,9 @@ pub const TRANSFORM_IDENTITY: Transform = [
 pub fn launch<Args: 'static, A: App<Args> + 'static>(
     settings: crate::Settings<Args>,
 ) -> Result<()> {
+    settings.validate()?;
     let info = AppInfo::default()
-        .validation(cfg!(debug_assertions))
+        .validation(settings.validation_layers)
         .name(settings.name.clone())?;
     watertender::starter_kit::launch::<EngineWrapper<Args, A>, _>(info, settings.vr, settings)
 }
@@ -73,6 +96,7 @@ impl<Args, A: App<Args>> MainLoop<Settings<Args>> for EngineWrapper<Args, A> {
         _core: &Core,
         mut platform: Platform,
     ) -> Result<()> {
+        self.engine.event(&event);
         self.app.event(&mut self.engine, &mut platform, event)
     }
 }
@@ -92,22 +116,40 @@ impl UploadBuffer {
     /// Create a new buffer initialized with `data`.
     pub fn new(core: &SharedCore, data: &[u8], dynamic: bool) -> Result<Self> {
         let mut instance = Self::new_empty(core, data.len() as _, dynamic)?;
-        match &mut instance {
-            Self::Static(buf) => buf.write_bytes(0, data)?,
+        instance.write_all(data)?;
+        Ok(instance)
+    }
+
+    /// Write `data` to the buffers for every frame
+    pub fn write_all(&mut self, data: &[u8]) -> Result<()> {
+        match self {
+            Self::Static(buf) => buf.write_bytes(0, data),
             Self::Dynamic(bufs) => {
                 for buf in bufs {
                     buf.write_bytes(0, data)?;
                 }
+                Ok(())
             }
         }
-        Ok(instance)
     }
 
-    /// Write to a dynamic buffer. Panics if the buffer is not dynamic
-    pub fn write(&mut self, frame: usize, data: &[u8]) -> Result<()> {
+    /// Whether this buffer may be written to after creation
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Self::Dynamic(_))
+    }
+
+    /// Write to a dynamic buffer at the given offset in bytes. Returns `Error::NotDynamic` if the
+    /// buffer is not dynamic
+    pub fn write(
+        &mut self,
+        frame: usize,
+        offset: u64,
+        data: &[u8],
+        kind: ResourceKind,
+    ) -> Result<()> {
         match self {
-            Self::Static(_) => panic!("Attempted to write to a static buffer"),
-            Self::Dynamic(bufs) => bufs[frame].write_bytes(0, data),
+            Self::Static(_) => Err(Error::NotDynamic(kind).into()),
+            Self::Dynamic(bufs) => bufs[frame].write_bytes(offset, data),
         }
     }
 
@@ -140,15 +182,37 @@ impl UploadBuffer {
 enum QueuedUpload {
     VertexBuffer(VertexBuffer),
     IndexBuffer(IndexBuffer),
-    //Texture(Texture),
+    InstanceBuffer(InstanceBuffer),
+    StorageBuffer(StorageBuffer),
+    UniformBuffer(UniformBuffer),
+    Texture(Texture),
 }
 
-/// All data inside the scene UBO
+/// A column-major 4x4 matrix for each view
+type ViewMatrices = [[f32; 4 * 4]; 2];
+
+/// All data inside the scene UBO. New fields are appended after `time`, so shaders which declare
+/// only the original members keep working. Must match the std140 layout of `PerFrame` in
+/// `shaders/idek.glsl`.
 #[repr(C)]
 #[derive(Copy, Clone, Debug)]
 struct SceneData {
     cameras: [f32; 4 * 4 * 2],
     time: f32,
+    /// Pads the following matrices to a 16-byte boundary
+    _pad: [f32; 3],
+    view: ViewMatrices,
+    projection: ViewMatrices,
+    inverse_view: ViewMatrices,
+    inverse_projection: ViewMatrices,
+    /// Viewport size in pixels
+    resolution: [f32; 2],
+    /// Seconds since the previous frame
+    delta_time: f32,
+    /// Number of frames rendered before this one, starting at 0
+    frame_index: u32,
+    /// Cursor position in pixels, from the top-left of the window
+    cursor: [f32; 2],
 }
 
 unsafe impl bytemuck::Zeroable for SceneData {}
@@ -160,114 +224,380 @@ struct SyncMemory {
     gpu: ManagedBuffer,
     /// CPU-side memory (UPLOAD)
     cpu: UploadBuffer,
-    /// Size in bytes
+    /// Size of the current contents in bytes
     size_bytes: u64,
+    /// Size of the allocations in bytes
+    capacity_bytes: u64,
     /// Length (# of vertices, indices, instances)
     length: u32,
+    /// Usage of the GPU-side memory, kept in order to reallocate
+    usage: vk::BufferUsageFlags,
+    /// Byte ranges written since the last upload
+    dirty: Vec<Range<u64>>,
+    /// Type of the elements, if this is an index buffer
+    index_type: vk::IndexType,
+}
+
+impl SyncMemory {
+    /// Create GPU memory with the given usage (in addition to TRANSFER_DST), and CPU memory
+    /// initialized with `data`. `length` is the number of elements in `data`.
+    pub fn new(
+        core: &SharedCore,
+        data: &[u8],
+        length: usize,
+        usage: vk::BufferUsageFlags,
+        dynamic: bool,
+    ) -> Result<Self> {
+        let mut instance = Self::new_empty(core, data.len() as u64, usage, dynamic)?;
+        instance.cpu.write_all(data)?;
+        instance.size_bytes = data.len() as u64;
+        instance.length = length as _;
+        instance.dirty.push(0..instance.size_bytes);
+        Ok(instance)
+    }
+
+    /// Create empty memory with the given capacity in bytes
+    fn new_empty(
+        core: &SharedCore,
+        capacity_bytes: u64,
+        usage: vk::BufferUsageFlags,
+        dynamic: bool,
+    ) -> Result<Self> {
+        // Zero-sized buffers are not allowed
+        let capacity_bytes = capacity_bytes.max(1);
+
+        let ci = vk::BufferCreateInfoBuilder::new()
+            .usage(vk::BufferUsageFlags::TRANSFER_DST | usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .queue_family_indices(&[])
+            .size(capacity_bytes);
+
+        let gpu = ManagedBuffer::new(core.clone(), ci, UsageFlags::FAST_DEVICE_ACCESS)?;
+
+        let cpu = UploadBuffer::new_empty(core, capacity_bytes, dynamic)?;
+
+        Ok(Self {
+            cpu,
+            gpu,
+            size_bytes: 0,
+            capacity_bytes,
+            length: 0,
+            usage,
+            dirty: vec![],
+            index_type: vk::IndexType::UINT32,
+        })
+    }
+
+    /// Write to the CPU-side memory for this frame, and set the length to `length` elements.
+    /// If the data does not fit, new memory is allocated with at least double the capacity, and
+    /// the old memory is returned; it may still be in use by frames in flight.
+    pub fn write(
+        &mut self,
+        core: &SharedCore,
+        frame: usize,
+        data: &[u8],
+        length: usize,
+        kind: ResourceKind,
+    ) -> Result<Option<SyncMemory>> {
+        if !self.cpu.is_dynamic() {
+            return Err(Error::NotDynamic(kind).into());
+        }
+
+        let size_bytes = data.len() as u64;
+        let mut retired = None;
+        if size_bytes > self.capacity_bytes {
+            let capacity_bytes = size_bytes.max(self.capacity_bytes * 2);
+            let mut new = Self::new_empty(core, capacity_bytes, self.usage, true)?;
+            new.index_type = self.index_type;
+            retired = Some(std::mem::replace(self, new));
+        }
+
+        self.cpu.write(frame, 0, data, kind)?;
+        self.size_bytes = size_bytes;
+        self.length = length as _;
+
+        // The whole buffer is replaced, so any earlier partial writes are superseded
+        self.dirty.clear();
+        self.dirty.push(0..size_bytes);
+
+        Ok(retired)
+    }
+
+    /// Write to the CPU-side memory for this frame, starting at `offset` bytes. The written range
+    /// must lie within the current contents of the buffer; ranges ending past `u64::MAX` are
+    /// reported as out of bounds rather than overflowing.
+    pub fn write_range(
+        &mut self,
+        frame: usize,
+        offset: u64,
+        data: &[u8],
+        kind: ResourceKind,
+    ) -> Result<()> {
+        let end = offset.saturating_add(data.len() as u64);
+        if end > self.size_bytes {
+            return Err(Error::OutOfBounds {
+                kind,
+                end: end as usize,
+                size: self.size_bytes as usize,
+            }
+            .into());
+        }
+
+        self.cpu.write(frame, offset, data, kind)?;
+        if !data.is_empty() {
+            self.dirty.push(offset..end);
+        }
+
+        Ok(())
+    }
+
+    /// Take the ranges written since the last upload, sorted and with overlapping or adjacent
+    /// ranges merged
+    pub fn take_dirty_ranges(&mut self) -> Vec<Range<u64>> {
+        merge_ranges(std::mem::take(&mut self.dirty))
+    }
+}
+
+/// Sort the ranges, merging those which overlap or are adjacent
+fn merge_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    merged
+}
+
+/// Handles of buffers backed by `SyncMemory`
+trait BufferKey: slotmap::Key {
+    const KIND: ResourceKind;
+    /// Usage of the GPU-side memory
+    const USAGE: vk::BufferUsageFlags;
+    /// The buffers of this kind
+    fn buffers(engine: &mut Engine) -> &mut SlotMap<Self, SyncMemory>;
+    /// The upload job for this buffer
+    fn upload(self) -> QueuedUpload;
+}
+
+macro_rules! impl_buffer_key {
+    ($key:ident, $field:ident, $usage:ident) => {
+        impl BufferKey for $key {
+            const KIND: ResourceKind = ResourceKind::$key;
+            const USAGE: vk::BufferUsageFlags = vk::BufferUsageFlags::$usage;
+            fn buffers(engine: &mut Engine) -> &mut SlotMap<Self, SyncMemory> {
+                &mut engine.$field
+            }
+            fn upload(self) -> QueuedUpload {
+                QueuedUpload::$key(self)
+            }
+        }
+    };
+}
+
+impl_buffer_key!(VertexBuffer, vertex_bufs, VERTEX_BUFFER);
+impl_buffer_key!(IndexBuffer, index_bufs, INDEX_BUFFER);
+impl_buffer_key!(InstanceBuffer, instance_bufs, VERTEX_BUFFER);
+impl_buffer_key!(StorageBuffer, storage_bufs, STORAGE_BUFFER);
+impl_buffer_key!(UniformBuffer, uniform_bufs, UNIFORM_BUFFER);
+
+/// GPU-side image, and the CPU-side memory used to upload to it. Might be dynamic.
+struct TextureData {
+    /// GPU-side image (FAST_DEVICE_ACCESS)
+    image: ManagedImage,
+    view: vk::ImageView,
+    sampler: vk::Sampler,
+    /// Descriptor set containing only this texture, bound to set 1
+    descriptor_set: vk::DescriptorSet,
+    /// CPU-side memory (UPLOAD)
+    cpu: UploadBuffer,
+    extent: vk::Extent3D,
+}
+
+/// A resource freed by the user, which may still be in use by frames in flight
+enum Garbage {
+    Buffer(SyncMemory),
+    TransformBuffer(ManagedBuffer),
+    Pipeline(vk::Pipeline),
+    Texture(TextureData),
+}
+
+impl Garbage {
+    /// Destroy the resource. The device must no longer be using it.
+    unsafe fn destroy(self, core: &Core, texture_descriptor_pool: vk::DescriptorPool) {
+        match self {
+            // Memory is freed on drop
+            Garbage::Buffer(_) | Garbage::TransformBuffer(_) => (),
+            Garbage::Pipeline(pipeline) => core.device.destroy_pipeline(Some(pipeline), None),
+            Garbage::Texture(texture) => {
+                core.device.destroy_sampler(Some(texture.sampler), None);
+                core.device.destroy_image_view(Some(texture.view), None);
+                // Freeing descriptor sets cannot fail
+                let _ = core
+                    .device
+                    .free_descriptor_sets(texture_descriptor_pool, &[texture.descriptor_set]);
+            }
+        }
+    }
+}
+
+/// Maximum number of textures which may exist at once
+const MAX_TEXTURES: u32 = 1024;
+
+/// Format of all textures
+const TEXTURE_FORMAT: vk::Format = vk::Format::R8G8B8A8_SRGB;
+
+/// A graphics pipeline, along with the vertex type it was created for
+struct ShaderData {
+    pipeline: vk::Pipeline,
+    layout: VertexLayoutInfo,
+    /// User buffers attached to the shader, used by every draw unless overridden
+    buffers: BufferBindings,
 }
 
 /// The engine object. Also known as the "Context" from within usercode.
 pub struct Engine {
     vertex_bufs: SlotMap<VertexBuffer, SyncMemory>,
+    /// Vertex type of each vertex buffer
+    vertex_layouts: SecondaryMap<VertexBuffer, LayoutId>,
     index_bufs: SlotMap<IndexBuffer, SyncMemory>,
-    //instance_bufs: SlotMap<InstanceBuffer, SyncMemory>,
-    shaders: SlotMap<Shader, vk::Pipeline>,
-    //textures: SlotMap<Texture, (ManagedImage, UploadBuffer)>,
+    instance_bufs: SlotMap<InstanceBuffer, SyncMemory>,
+    storage_bufs: SlotMap<StorageBuffer, SyncMemory>,
+    uniform_bufs: SlotMap<UniformBuffer, SyncMemory>,
+    shaders: SlotMap<Shader, ShaderData>,
+    textures: SlotMap<Texture, TextureData>,
     /// Trivial built-in shader
     default_shader_key: Shader,
+    /// Trivial built-in shader, reading per-instance transforms and colors
+    default_instanced_shader_key: Shader,
 
     descriptor_sets: Vec<vk::DescriptorSet>,
     descriptor_pool: vk::DescriptorPool,
     descriptor_set_layout: vk::DescriptorSetLayout,
 
+    texture_descriptor_pool: vk::DescriptorPool,
+    texture_set_layout: vk::DescriptorSetLayout,
+
+    /// Descriptor sets for user buffers
+    buffer_sets: BufferSets,
+
     pipeline_layout: vk::PipelineLayout,
+    /// Space available for user uniforms in push constants, in bytes
+    max_uniforms_size: usize,
 
     scene_ubo: FrameDataUbo<SceneData>,
     starter_kit: StarterKit,
 
+    /// Transform buffers for each frame in flight
     transforms: Vec<ManagedBuffer>,
+    /// Capacity of each transform buffer, in number of transforms
+    transform_capacities: Vec<usize>,
 
     camera_prefix: Matrix4<f32>,
+    /// View and projection matrices reported to shaders in windowed mode
+    view: Matrix4<f32>,
+    projection: Matrix4<f32>,
+
+    /// Last known cursor position in pixels
+    cursor_position: [f32; 2],
+    /// Start of the previous frame
+    last_frame: Instant,
+
+    window_title: WindowTitle,
+    /// Fullscreen state to apply to the window on the next frame
+    requested_fullscreen: Option<bool>,
+
+    background: Background,
+    /// Built-in shader for `Background::Gradient`, created when first needed
+    gradient_shader: Option<Shader>,
 
     /// Uploads to be completed during the next frame
     queued_uploads: Vec<QueuedUpload>,
 
+    /// Freed resources, along with the frame number they were freed on
+    deletion_queue: Vec<(u64, Garbage)>,
+
+    /// Physical device in use
+    device_info: DeviceInfo,
+
+    /// Draws skipped during the last frame
+    draw_errors: Vec<DrawError>,
+
+    /// Number of frames started so far, including the one being recorded
+    frame_count: u64,
+
+    /// Watches the files of shaders created with `shader_from_files`, created on first use
+    #[cfg(feature = "hot-reload")]
+    shader_watcher: Option<ShaderWatcher>,
+    /// Errors from rebuilding shaders, until taken by the app
+    #[cfg(feature = "hot-reload")]
+    shader_reload_errors: Vec<anyhow::Error>,
+
+    /// MSAA samples in use, which may be fewer than requested
+    msaa_samples: u8,
+
     start_time: Instant,
 }
 
-// type Instance = ();
-
 // Public functions ("Context")
 impl Engine {
     /// Upload a set of vertices
     pub fn vertices(&mut self, vertices: &[Vertex], dynamic: bool) -> Result<VertexBuffer> {
-        let size_bytes = std::mem::size_of_val(vertices) as u64;
-        let ci = vk::BufferCreateInfoBuilder::new()
-            .usage(vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::VERTEX_BUFFER)
-            .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .queue_family_indices(&[])
-            .size(size_bytes);
-
-        let gpu_buf = ManagedBuffer::new(
-            self.starter_kit.core.clone(),
-            ci,
-            UsageFlags::FAST_DEVICE_ACCESS,
-        )?;
-
-        let upload_buf = UploadBuffer::new(
-            &self.starter_kit.core,
-            bytemuck::cast_slice(vertices),
-            dynamic,
-        )?;
-
-        let key = self.vertex_bufs.insert(SyncMemory {
-            cpu: upload_buf,
-            gpu: gpu_buf,
-            size_bytes,
-            length: vertices.len() as _,
-        });
-
-        self.queued_uploads.push(QueuedUpload::VertexBuffer(key));
+        self.vertices_of(vertices, dynamic)
+    }
 
+    /// Upload a set of vertices of a custom type. The buffer may only be drawn with shaders
+    /// created for the same vertex type (see `shader_of`).
+    pub fn vertices_of<V: VertexLayout>(
+        &mut self,
+        vertices: &[V],
+        dynamic: bool,
+    ) -> Result<VertexBuffer> {
+        let key = self.create_buffer(bytemuck::cast_slice(vertices), vertices.len(), dynamic)?;
+        self.vertex_layouts.insert(key, LayoutId::of::<V>());
         Ok(key)
     }
 
     /// Upload a set of indices
     pub fn indices(&mut self, indices: &[u32], dynamic: bool) -> Result<IndexBuffer> {
-        let size_bytes = std::mem::size_of_val(indices) as u64;
-        let ci = vk::BufferCreateInfoBuilder::new()
-            .usage(vk::BufferUsageFlags::TRANSFER_DST | vk::BufferUsageFlags::INDEX_BUFFER)
-            .sharing_mode(vk::SharingMode::EXCLUSIVE)
-            .queue_family_indices(&[])
-            .size(size_bytes);
-
-        let gpu_buf = ManagedBuffer::new(
-            self.starter_kit.core.clone(),
-            ci,
-            UsageFlags::FAST_DEVICE_ACCESS,
-        )?;
+        self.indices_of(indices, dynamic)
+    }
 
-        let upload_buf = UploadBuffer::new(
-            &self.starter_kit.core,
-            bytemuck::cast_slice(indices),
-            dynamic,
-        )?;
+    /// Upload a set of 16-bit indices
+    pub fn indices_u16(&mut self, indices: &[u16], dynamic: bool) -> Result<IndexBuffer> {
+        self.indices_of(indices, dynamic)
+    }
 
-        let key = self.index_bufs.insert(SyncMemory {
-            cpu: upload_buf,
-            gpu: gpu_buf,
-            size_bytes,
-            length: indices.len() as _,
-        });
+    /// Upload a set of indices of either index type. Updates must use the same type.
+    pub fn indices_of<I: Index>(&mut self, indices: &[I], dynamic: bool) -> Result<IndexBuffer> {
+        let key: IndexBuffer =
+            self.create_buffer(bytemuck::cast_slice(indices), indices.len(), dynamic)?;
+        self.index_bufs[key].index_type = I::index_type();
+        Ok(key)
+    }
 
-        self.queued_uploads.push(QueuedUpload::IndexBuffer(key));
+    /// Upload a set of instances. Draws using these will be instanced, and use
+    /// `DEFAULT_INSTANCED_VERTEX_SHADER` unless another shader is specified.
+    pub fn instances(&mut self, instances: &[Instance], dynamic: bool) -> Result<InstanceBuffer> {
+        self.create_buffer(bytemuck::cast_slice(instances), instances.len(), dynamic)
+    }
 
-        Ok(key)
+    /// Upload data to a storage buffer, which custom shaders may read at `set = 2, binding = slot`
+    /// once it is attached to a shader or draw. Shaders must declare it `readonly`; the
+    /// device features needed to store to it from graphics shaders are not enabled.
+    pub fn storage_buffer(&mut self, data: &[u8], dynamic: bool) -> Result<StorageBuffer> {
+        self.create_buffer(data, data.len(), dynamic)
     }
 
-    /*pub fn instances(&mut self, instances: &[Instance], dynamic: bool) -> Result<InstanceBuffer> {
-        todo!()
-    }*/
+    /// Upload data to a uniform buffer, which custom shaders may read at
+    /// `set = 2, binding = STORAGE_BUFFER_SLOTS + slot` once it is attached to a shader or draw
+    pub fn uniform_buffer(&mut self, data: &[u8], dynamic: bool) -> Result<UniformBuffer> {
+        self.create_buffer(data, data.len(), dynamic)
+    }
 
     /// Upload a shader
     pub fn shader(
@@ -276,42 +606,441 @@ impl Engine {
         fragment: &[u8],
         primitive: Primitive,
     ) -> Result<Shader> {
-        Ok(self.shaders.insert(shader(
-            &self.starter_kit.core,
+        self.shader_with(vertex, fragment, primitive, ShaderSettings::default())
+    }
+
+    /// Same as `shader`, but with the specified blending, depth and rasterization settings
+    pub fn shader_with(
+        &mut self,
+        vertex: &[u8],
+        fragment: &[u8],
+        primitive: Primitive,
+        settings: ShaderSettings,
+    ) -> Result<Shader> {
+        self.shader_of::<Vertex>(vertex, fragment, primitive, settings)
+    }
+
+    /// Same as `shader_with`, but for vertex buffers of a custom vertex type (see `vertices_of`)
+    pub fn shader_of<V: VertexLayout>(
+        &mut self,
+        vertex: &[u8],
+        fragment: &[u8],
+        primitive: Primitive,
+        settings: ShaderSettings,
+    ) -> Result<Shader> {
+        let layout = VertexLayoutInfo::of::<V>();
+        self.shader_with_layout(vertex, fragment, primitive, layout, settings)
+    }
+
+    /// Create a shader reading vertices with the given layout
+    fn shader_with_layout(
+        &mut self,
+        vertex: &[u8],
+        fragment: &[u8],
+        primitive: Primitive,
+        layout: VertexLayoutInfo,
+        settings: ShaderSettings,
+    ) -> Result<Shader> {
+        let pipeline =
+            self.build_pipeline(vertex, fragment, primitive.into(), &layout, &settings)?;
+        Ok(self.shaders.insert(ShaderData {
+            pipeline,
+            layout,
+            buffers: BufferBindings::default(),
+        }))
+    }
+
+    /// Compile and upload the given shader source. The built-in declarations may be included
+    /// with `#include <idek.glsl>`
+    #[cfg(feature = "shaderc")]
+    pub fn shader_glsl(
+        &mut self,
+        vertex: &str,
+        fragment: &str,
+        primitive: Primitive,
+    ) -> Result<Shader> {
+        let vertex = compile_glsl(vertex, shaderc::ShaderKind::Vertex, "vertex.glsl")?;
+        let fragment = compile_glsl(fragment, shaderc::ShaderKind::Fragment, "fragment.glsl")?;
+        self.shader(&vertex, &fragment, primitive)
+    }
+
+    /// Create a shader which reads no vertex attributes, for use with `DrawCmd::procedural`
+    pub fn procedural_shader(
+        &mut self,
+        vertex: &[u8],
+        fragment: &[u8],
+        primitive: Primitive,
+    ) -> Result<Shader> {
+        self.shader_with_layout(
             vertex,
             fragment,
-            primitive.into(),
-            self.starter_kit.render_pass,
-            self.pipeline_layout,
-            self.starter_kit.msaa_samples,
-        )?))
+            primitive,
+            VertexLayoutInfo::none(),
+            ShaderSettings::default(),
+        )
     }
 
-    /// Compile and upload the given shader source
+    /// Same as `procedural_shader`, but compiled from GLSL source like `shader_glsl`
     #[cfg(feature = "shaderc")]
-    pub fn shader_glsl(
+    pub fn procedural_shader_glsl(
         &mut self,
         vertex: &str,
         fragment: &str,
         primitive: Primitive,
     ) -> Result<Shader> {
-        todo!()
+        let vertex = compile_glsl(vertex, shaderc::ShaderKind::Vertex, "vertex.glsl")?;
+        let fragment = compile_glsl(fragment, shaderc::ShaderKind::Fragment, "fragment.glsl")?;
+        self.procedural_shader(&vertex, &fragment, primitive)
+    }
+
+    /// Load a shader from the given files. SPIR-V files must end in `.spv`, anything else is
+    /// compiled as GLSL (requires the `shaderc` feature). With the `hot-reload` feature, the files
+    /// are watched and the shader is rebuilt whenever they change; if rebuilding fails, the last
+    /// working version is kept and the error is returned by `take_shader_reload_errors`.
+    pub fn shader_from_files(
+        &mut self,
+        vertex: impl AsRef<Path>,
+        fragment: impl AsRef<Path>,
+        primitive: Primitive,
+    ) -> Result<Shader> {
+        self.shader_from_files_with(vertex, fragment, primitive, ShaderSettings::default())
+    }
+
+    /// Same as `shader_from_files`, but with the specified blending, depth and rasterization
+    /// settings
+    pub fn shader_from_files_with(
+        &mut self,
+        vertex: impl AsRef<Path>,
+        fragment: impl AsRef<Path>,
+        primitive: Primitive,
+        settings: ShaderSettings,
+    ) -> Result<Shader> {
+        let (vertex, fragment) = (vertex.as_ref(), fragment.as_ref());
+        let topology: vk::PrimitiveTopology = primitive.into();
+        let layout = VertexLayoutInfo::of::<Vertex>();
+        let pipeline = self.load_pipeline(vertex, fragment, topology, &layout, &settings)?;
+        let handle = self.shaders.insert(ShaderData {
+            pipeline,
+            layout,
+            buffers: BufferBindings::default(),
+        });
+
+        #[cfg(feature = "hot-reload")]
+        {
+            let watcher = match &mut self.shader_watcher {
+                Some(watcher) => watcher,
+                None => self.shader_watcher.insert(ShaderWatcher::new()?),
+            };
+            watcher.track(
+                handle,
+                TrackedShader {
+                    vertex: vertex.to_path_buf(),
+                    fragment: fragment.to_path_buf(),
+                    primitive: topology,
+                    settings,
+                },
+            )?;
+        }
+
+        Ok(handle)
+    }
+
+    /// Create a shader which covers the whole screen (or each eye in VR) with the given fragment
+    /// shader. The fragment shader receives UV coordinates as `layout(location = 0) in vec2`,
+    /// from (0, 0) at the top-left to (1, 1) at the bottom-right. Draw it with
+    /// `DrawCmd::fullscreen`.
+    pub fn fullscreen_shader(&mut self, fragment: &[u8]) -> Result<Shader> {
+        let settings = ShaderSettings::default()
+            .depth_test(false)
+            .depth_write(false)
+            .cull_mode(CullMode::None);
+        self.shader_with_layout(
+            FULLSCREEN_VERTEX_SHADER,
+            fragment,
+            Primitive::Triangles,
+            VertexLayoutInfo::none(),
+            settings,
+        )
+    }
+
+    /// Attach a storage buffer to the given slot of a shader, so that every draw using the shader
+    /// binds it (unless the draw binds its own buffer in that slot)
+    pub fn attach_storage_buffer(
+        &mut self,
+        shader: Shader,
+        slot: usize,
+        buffer: StorageBuffer,
+    ) -> Result<()> {
+        if slot >= STORAGE_BUFFER_SLOTS {
+            return Err(Error::InvalidSlot {
+                kind: ResourceKind::StorageBuffer,
+                slot,
+                slots: STORAGE_BUFFER_SLOTS,
+            }
+            .into());
+        }
+        if !self.storage_bufs.contains_key(buffer) {
+            return Err(Error::InvalidHandle(ResourceKind::StorageBuffer).into());
+        }
+        let shader = self
+            .shaders
+            .get_mut(shader)
+            .ok_or(Error::InvalidHandle(ResourceKind::Shader))?;
+        shader.buffers.storage[slot] = Some(buffer);
+        Ok(())
+    }
+
+    /// Attach a uniform buffer to the given slot of a shader, so that every draw using the shader
+    /// binds it (unless the draw binds its own buffer in that slot)
+    pub fn attach_uniform_buffer(
+        &mut self,
+        shader: Shader,
+        slot: usize,
+        buffer: UniformBuffer,
+    ) -> Result<()> {
+        if slot >= UNIFORM_BUFFER_SLOTS {
+            return Err(Error::InvalidSlot {
+                kind: ResourceKind::UniformBuffer,
+                slot,
+                slots: UNIFORM_BUFFER_SLOTS,
+            }
+            .into());
+        }
+        if !self.uniform_bufs.contains_key(buffer) {
+            return Err(Error::InvalidHandle(ResourceKind::UniformBuffer).into());
+        }
+        let shader = self
+            .shaders
+            .get_mut(shader)
+            .ok_or(Error::InvalidHandle(ResourceKind::Shader))?;
+        shader.buffers.uniform[slot] = Some(buffer);
+        Ok(())
+    }
+
+    /// Detach the storage buffer in the given slot of a shader, if any
+    pub fn detach_storage_buffer(&mut self, shader: Shader, slot: usize) -> Result<()> {
+        if slot >= STORAGE_BUFFER_SLOTS {
+            return Err(Error::InvalidSlot {
+                kind: ResourceKind::StorageBuffer,
+                slot,
+                slots: STORAGE_BUFFER_SLOTS,
+            }
+            .into());
+        }
+        let shader = self
+            .shaders
+            .get_mut(shader)
+            .ok_or(Error::InvalidHandle(ResourceKind::Shader))?;
+        shader.buffers.storage[slot] = None;
+        Ok(())
+    }
+
+    /// Detach the uniform buffer in the given slot of a shader, if any
+    pub fn detach_uniform_buffer(&mut self, shader: Shader, slot: usize) -> Result<()> {
+        if slot >= UNIFORM_BUFFER_SLOTS {
+            return Err(Error::InvalidSlot {
+                kind: ResourceKind::UniformBuffer,
+                slot,
+                slots: UNIFORM_BUFFER_SLOTS,
+            }
+            .into());
+        }
+        let shader = self
+            .shaders
+            .get_mut(shader)
+            .ok_or(Error::InvalidHandle(ResourceKind::Shader))?;
+        shader.buffers.uniform[slot] = None;
+        Ok(())
     }
 
-    /*
     /// Create a new texture containing the specified data with the specified width. Data must be
     /// 8-bit RGBA (4 bytes per pixel), and must be in row-major order.
     pub fn texture(&mut self, data: &[u8], width: usize, dynamic: bool) -> Result<Texture> {
+        self.texture_with(data, width, dynamic, SamplerSettings::default())
+    }
+
+    /// Same as `texture`, but with the specified sampling settings
+    pub fn texture_with(
+        &mut self,
+        data: &[u8],
+        width: usize,
+        dynamic: bool,
+        sampler: SamplerSettings,
+    ) -> Result<Texture> {
         ensure!(data.len() % 4 == 0, "Image data must be RGBA");
         let total_pixels = data.len() / 4;
+        let image_height = total_pixels.checked_div(width).unwrap_or(0);
+        self.check_texture_size(width, image_height)?;
         ensure!(
             total_pixels % width == 0,
             "Image data length must be a multiple of width"
         );
-        let image_height = total_pixels / width;
-        todo!()
+
+        let core = &self.starter_kit.core;
+
+        let extent = vk::Extent3D {
+            width: width as u32,
+            height: image_height as u32,
+            depth: 1,
+        };
+
+        // Image
+        let ci = vk::ImageCreateInfoBuilder::new()
+            .image_type(vk::ImageType::_2D)
+            .extent(extent)
+            .mip_levels(1)
+            .array_layers(1)
+            .format(TEXTURE_FORMAT)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .usage(vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .samples(vk::SampleCountFlagBits::_1);
+
+        let image = ManagedImage::new(core.clone(), ci, UsageFlags::FAST_DEVICE_ACCESS)?;
+
+        // Image view
+        let ci = vk::ImageViewCreateInfoBuilder::new()
+            .image(image.instance())
+            .view_type(vk::ImageViewType::_2D)
+            .format(TEXTURE_FORMAT)
+            .subresource_range(color_subresource_range());
+
+        let view = unsafe { core.device.create_image_view(&ci, None, None) }.result()?;
+
+        // Sampler
+        let address_mode = sampler.address_mode.into();
+        let ci = vk::SamplerCreateInfoBuilder::new()
+            .mag_filter(sampler.mag_filter.into())
+            .min_filter(sampler.min_filter.into())
+            .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
+            .address_mode_u(address_mode)
+            .address_mode_v(address_mode)
+            .address_mode_w(address_mode)
+            .anisotropy_enable(false)
+            .max_anisotropy(1.)
+            .border_color(vk::BorderColor::INT_OPAQUE_BLACK)
+            .unnormalized_coordinates(false)
+            .compare_enable(false)
+            .compare_op(vk::CompareOp::ALWAYS)
+            .mip_lod_bias(0.)
+            .min_lod(0.)
+            .max_lod(0.);
+
+        let sampler = unsafe { core.device.create_sampler(&ci, None, None) }.result()?;
+
+        // Descriptor set
+        let layouts = [self.texture_set_layout];
+        let create_info = vk::DescriptorSetAllocateInfoBuilder::new()
+            .descriptor_pool(self.texture_descriptor_pool)
+            .set_layouts(&layouts);
+
+        let descriptor_set =
+            unsafe { core.device.allocate_descriptor_sets(&create_info) }.result()?[0];
+
+        let image_infos = [vk::DescriptorImageInfoBuilder::new()
+            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .image_view(view)
+            .sampler(sampler)];
+
+        let writes = [vk::WriteDescriptorSetBuilder::new()
+            .image_info(&image_infos)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .dst_set(descriptor_set)
+            .dst_binding(0)
+            .dst_array_element(0)];
+
+        unsafe {
+            core.device.update_descriptor_sets(&writes, &[]);
+        }
+
+        let cpu = UploadBuffer::new(core, data, dynamic)?;
+
+        let key = self.textures.insert(TextureData {
+            image,
+            view,
+            sampler,
+            descriptor_set,
+            cpu,
+            extent,
+        });
+
+        self.queued_uploads.push(QueuedUpload::Texture(key));
+
+        Ok(key)
+    }
+
+    /// Free a vertex buffer. The handle is invalid after this call.
+    pub fn free_vertices(&mut self, handle: VertexBuffer) -> Result<()> {
+        self.free_buffer(handle)?;
+        self.vertex_layouts.remove(handle);
+        Ok(())
+    }
+
+    /// Free an index buffer. The handle is invalid after this call.
+    pub fn free_indices(&mut self, handle: IndexBuffer) -> Result<()> {
+        self.free_buffer(handle)
+    }
+
+    /// Free an instance buffer. The handle is invalid after this call.
+    pub fn free_instances(&mut self, handle: InstanceBuffer) -> Result<()> {
+        self.free_buffer(handle)
+    }
+
+    /// Free a storage buffer, detaching it from any shaders. The handle is invalid after this
+    /// call.
+    pub fn free_storage_buffer(&mut self, handle: StorageBuffer) -> Result<()> {
+        self.free_buffer(handle)?;
+        for shader in self.shaders.values_mut() {
+            for slot in shader.buffers.storage.iter_mut() {
+                if *slot == Some(handle) {
+                    *slot = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Free a uniform buffer, detaching it from any shaders. The handle is invalid after this
+    /// call.
+    pub fn free_uniform_buffer(&mut self, handle: UniformBuffer) -> Result<()> {
+        self.free_buffer(handle)?;
+        for shader in self.shaders.values_mut() {
+            for slot in shader.buffers.uniform.iter_mut() {
+                if *slot == Some(handle) {
+                    *slot = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Free a shader. The handle is invalid after this call. Built-in shaders cannot be freed.
+    pub fn free_shader(&mut self, handle: Shader) -> Result<()> {
+        if handle == self.default_shader_key || handle == self.default_instanced_shader_key {
+            return Err(Error::BuiltIn(ResourceKind::Shader).into());
+        }
+        let shader = self
+            .shaders
+            .remove(handle)
+            .ok_or(Error::InvalidHandle(ResourceKind::Shader))?;
+        self.defer_deletion(Garbage::Pipeline(shader.pipeline));
+        #[cfg(feature = "hot-reload")]
+        if let Some(watcher) = &mut self.shader_watcher {
+            watcher.untrack(handle);
+        }
+        Ok(())
+    }
+
+    /// Free a texture. The handle is invalid after this call.
+    pub fn free_texture(&mut self, handle: Texture) -> Result<()> {
+        let texture = self
+            .textures
+            .remove(handle)
+            .ok_or(Error::InvalidHandle(ResourceKind::Texture))?;
+        self.defer_deletion(Garbage::Texture(texture));
+        Ok(())
     }
-    */
 
     /// Returns the current screen size in pixels
     /// (width, height)
@@ -320,49 +1049,279 @@ impl Engine {
         (extent.width, extent.height)
     }
 
+    /// Return the name, type, limits and supported features of the device in use. The features
+    /// enabled on the logical device cannot be reported, as watertender creates it without
+    /// telling idek which ones it enabled.
+    pub fn device_info(&self) -> &DeviceInfo {
+        &self.device_info
+    }
+
+    /// Return the draws which were skipped during the last frame because they were invalid, such
+    /// as those referring to freed resources. Empty if every draw was valid.
+    pub fn last_frame_errors(&self) -> &[DrawError] {
+        &self.draw_errors
+    }
+
+    /// Return the number of MSAA samples in use. This is lower than `Settings::msaa_samples` if
+    /// the device does not support as many.
+    pub fn msaa_samples(&self) -> u8 {
+        self.msaa_samples
+    }
+
+    /// Take the errors from rebuilding shaders whose files changed, since the last call. The
+    /// previous version of each shader is kept when rebuilding fails.
+    #[cfg(feature = "hot-reload")]
+    pub fn take_shader_reload_errors(&mut self) -> Vec<anyhow::Error> {
+        std::mem::take(&mut self.shader_reload_errors)
+    }
+
     /// Return the time since the engine started
     pub fn start_time(&self) -> Instant {
         self.start_time
     }
 
     /// Set the camera prefix. This transformation is applied to each vertex. In the OpenXR backend,
-    /// this is applied before the camera view and projection matrices
+    /// this is applied before the camera view and projection matrices. In windowed mode, shaders
+    /// see it as the view matrix, with an identity projection matrix.
     pub fn set_camera_prefix(&mut self, matrix: Matrix4<f32>) {
         self.camera_prefix = matrix;
+        self.view = matrix;
+        self.projection = Matrix4::identity();
+    }
+
+    /// Set the view and projection matrices separately, so that shaders can use them
+    /// individually. The camera prefix becomes `projection * view`.
+    pub fn set_view_projection(&mut self, view: Matrix4<f32>, projection: Matrix4<f32>) {
+        self.camera_prefix = projection * view;
+        self.view = view;
+        self.projection = projection;
+    }
+
+    /// Returns the last known cursor position in pixels, from the top-left of the window
+    pub fn cursor_position(&self) -> (f32, f32) {
+        let [x, y] = self.cursor_position;
+        (x, y)
+    }
+
+    /// Set the color the screen is cleared to each frame
+    pub fn set_clear_color(&mut self, color: [f32; 4]) {
+        self.background = Background::Color(color);
+    }
+
+    /// Set the background drawn each frame, in both windowed mode and VR
+    pub fn set_background(&mut self, background: Background) -> Result<()> {
+        if let (Background::Gradient { .. }, None) = (background, self.gradient_shader) {
+            self.gradient_shader = Some(self.fullscreen_shader(GRADIENT_FRAGMENT_SHADER)?);
+        }
+        self.background = background;
+        Ok(())
+    }
+
+    /// Current background
+    pub fn background(&self) -> Background {
+        self.background
+    }
+
+    /// Set the window title, shown from the next frame onwards. Has no effect in VR.
+    pub fn set_window_title(&mut self, title: &str) {
+        self.window_title.set_title(title);
+    }
+
+    /// Show or hide the frame rate and frame time after the window title
+    pub fn set_show_fps(&mut self, show_fps: bool) {
+        self.window_title.set_show_fps(show_fps);
+    }
+
+    /// Switch between borderless fullscreen and windowed mode on the next frame. Has no effect in
+    /// VR.
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.requested_fullscreen = Some(fullscreen);
+    }
+
+    /// Dynamically upload vertices. Possibly only if the buffer was created as dynamic.
+    /// The number of vertices may differ from the original buffer; it grows as needed.
+    /// The vertex type must match the one the buffer was created with.
+    pub fn update_vertices<V: VertexLayout>(
+        &mut self,
+        handle: VertexBuffer,
+        vertices: &[V],
+    ) -> Result<()> {
+        self.check_vertex_layout::<V>(handle)?;
+        self.write_buffer(handle, bytemuck::cast_slice(vertices), vertices.len())
+    }
+
+    /// Dynamically upload indices. Possibly only if the buffer was created as dynamic.
+    /// The number of indices may differ from the original buffer; it grows as needed.
+    /// The index type must match the one the buffer was created with.
+    pub fn update_indices<I: Index>(&mut self, handle: IndexBuffer, indices: &[I]) -> Result<()> {
+        self.check_index_type::<I>(handle)?;
+        self.write_buffer(handle, bytemuck::cast_slice(indices), indices.len())
+    }
+
+    /// Dynamically upload instances. Possibly only if the buffer was created as dynamic.
+    /// The number of instances may differ from the original buffer; it grows as needed.
+    pub fn update_instances(
+        &mut self,
+        handle: InstanceBuffer,
+        instances: &[Instance],
+    ) -> Result<()> {
+        self.write_buffer(handle, bytemuck::cast_slice(instances), instances.len())
+    }
+
+    /// Dynamically upload part of a vertex buffer, starting at the vertex at `offset`. Possibly
+    /// only if the buffer was created as dynamic. Only the modified range is copied to the GPU.
+    pub fn update_vertices_range<V: VertexLayout>(
+        &mut self,
+        handle: VertexBuffer,
+        offset: usize,
+        vertices: &[V],
+    ) -> Result<()> {
+        self.check_vertex_layout::<V>(handle)?;
+        self.write_buffer_range(
+            handle,
+            offset.saturating_mul(std::mem::size_of::<V>()),
+            bytemuck::cast_slice(vertices),
+        )
+    }
+
+    /// Dynamically upload part of an index buffer, starting at the index at `offset`. Possibly
+    /// only if the buffer was created as dynamic. Only the modified range is copied to the GPU.
+    pub fn update_indices_range<I: Index>(
+        &mut self,
+        handle: IndexBuffer,
+        offset: usize,
+        indices: &[I],
+    ) -> Result<()> {
+        self.check_index_type::<I>(handle)?;
+        self.write_buffer_range(
+            handle,
+            offset.saturating_mul(std::mem::size_of::<I>()),
+            bytemuck::cast_slice(indices),
+        )
+    }
+
+    /// Dynamically upload part of an instance buffer, starting at the instance at `offset`.
+    /// Possibly only if the buffer was created as dynamic. Only the modified range is copied to
+    /// the GPU.
+    pub fn update_instances_range(
+        &mut self,
+        handle: InstanceBuffer,
+        offset: usize,
+        instances: &[Instance],
+    ) -> Result<()> {
+        self.write_buffer_range(
+            handle,
+            offset.saturating_mul(std::mem::size_of::<Instance>()),
+            bytemuck::cast_slice(instances),
+        )
+    }
+
+    /// Dynamically upload storage buffer data. Possibly only if the buffer was created as
+    /// dynamic. The size may differ from the original buffer; it grows as needed.
+    pub fn update_storage_buffer(&mut self, handle: StorageBuffer, data: &[u8]) -> Result<()> {
+        self.write_buffer(handle, data, data.len())
+    }
+
+    /// Dynamically upload uniform buffer data. Possibly only if the buffer was created as
+    /// dynamic. The size may differ from the original buffer; it grows as needed.
+    pub fn update_uniform_buffer(&mut self, handle: UniformBuffer, data: &[u8]) -> Result<()> {
+        self.write_buffer(handle, data, data.len())
+    }
+
+    /// Dynamically upload part of a storage buffer, starting at `offset` bytes. Possibly only if
+    /// the buffer was created as dynamic. Only the modified range is copied to the GPU.
+    pub fn update_storage_buffer_range(
+        &mut self,
+        handle: StorageBuffer,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<()> {
+        self.write_buffer_range(handle, offset, data)
+    }
+
+    /// Dynamically upload part of a uniform buffer, starting at `offset` bytes. Possibly only if
+    /// the buffer was created as dynamic. Only the modified range is copied to the GPU.
+    pub fn update_uniform_buffer_range(
+        &mut self,
+        handle: UniformBuffer,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<()> {
+        self.write_buffer_range(handle, offset, data)
+    }
+
+    /// Dynamically upload texture data. Possibly only if the texture was created as dynamic.
+    /// The data must be exactly the same size as the original texture.
+    pub fn update_texture(&mut self, handle: Texture, data: &[u8]) -> Result<()> {
+        let texture = self
+            .textures
+            .get_mut(handle)
+            .ok_or(Error::InvalidHandle(ResourceKind::Texture))?;
+        let extent = texture.extent;
+        let expected = extent.width as usize * extent.height as usize * 4;
+        if data.len() != expected {
+            return Err(Error::SizeMismatch {
+                kind: ResourceKind::Texture,
+                expected,
+                actual: data.len(),
+            }
+            .into());
+        }
+        texture
+            .cpu
+            .write(self.starter_kit.frame, 0, data, ResourceKind::Texture)?;
+        self.queued_uploads.push(QueuedUpload::Texture(handle));
+        Ok(())
     }
+}
 
-    /// Dynamically upload vertices. Possibly only if the buffer was created as dynamic
-    pub fn update_vertices(&mut self, handle: VertexBuffer, vertices: &[Vertex]) -> Result<()> {
-        let memory = self.vertex_bufs.get_mut(handle).unwrap();
-        let bytes = bytemuck::cast_slice(vertices);
-        //assert_eq!(bytes.len() as u64, memory.size, "Must write exactly as many vertices as the original buffer");
-        memory.cpu.write(self.starter_kit.frame, bytes)?;
-        self.queued_uploads.push(QueuedUpload::VertexBuffer(handle));
-        Ok(())
+/// Apply the window settings on startup. Has no effect in VR.
+fn configure_window<Args>(platform: &mut Platform, settings: &Settings<Args>) {
+    if let Platform::Winit { window, .. } = platform {
+        window.set_resizable(settings.resizable);
+        if let Some((width, height)) = settings.window_size {
+            window.set_inner_size(PhysicalSize::new(width, height));
+        }
+        if let Some((x, y)) = settings.window_position {
+            window.set_outer_position(PhysicalPosition::new(x, y));
+        }
+        if settings.fullscreen {
+            window.set_fullscreen(fullscreen_mode(true));
+        }
     }
+}
 
-    /// Dynamically upload indices. Possibly only if the buffer was created as dynamic
-    pub fn update_indices(&mut self, handle: IndexBuffer, indices: &[u32]) -> Result<()> {
-        let memory = self.index_bufs.get_mut(handle).unwrap();
-        let bytes = bytemuck::cast_slice(indices);
-        //assert_eq!(bytes.len() as u64, memory.size, "Must write exactly as many vertices as the original buffer");
-        memory.cpu.write(self.starter_kit.frame, bytes)?;
-        self.queued_uploads.push(QueuedUpload::IndexBuffer(handle));
-        Ok(())
+/// Borderless fullscreen on the current monitor, or windowed
+fn fullscreen_mode(fullscreen: bool) -> Option<Fullscreen> {
+    match fullscreen {
+        true => Some(Fullscreen::Borderless(None)),
+        false => None,
     }
 }
 
-fn create_transform_buffers(
-    core: &SharedCore,
-    max_transforms: usize,
-) -> Result<Vec<ManagedBuffer>> {
-    let total_size = std::mem::size_of::<Transform>() * max_transforms;
+/// Scene UBO binding in descriptor set 0
+const FRAME_DATA_BINDING: u32 = 0;
+
+/// Transforms SSBO binding in descriptor set 0
+const TRANSFORM_BINDING: u32 = 1;
+
+/// Shader stages which may read push constants
+const PUSH_CONSTANT_STAGES: vk::ShaderStageFlags = vk::ShaderStageFlags::from_bits_truncate(
+    vk::ShaderStageFlags::VERTEX.bits() | vk::ShaderStageFlags::FRAGMENT.bits(),
+);
+
+fn create_transform_buffer(core: &SharedCore, capacity: usize) -> Result<ManagedBuffer> {
+    let total_size = std::mem::size_of::<Transform>() * capacity.max(1);
     let ci = vk::BufferCreateInfoBuilder::new()
         .size(total_size as u64)
         .sharing_mode(vk::SharingMode::EXCLUSIVE)
         .usage(vk::BufferUsageFlags::STORAGE_BUFFER);
+    ManagedBuffer::new(core.clone(), ci, watertender::memory::UsageFlags::UPLOAD)
+}
+
+fn create_transform_buffers(core: &SharedCore, capacity: usize) -> Result<Vec<ManagedBuffer>> {
     (0..FRAMES_IN_FLIGHT)
-        .map(|_| ManagedBuffer::new(core.clone(), ci, watertender::memory::UsageFlags::UPLOAD))
+        .map(|_| create_transform_buffer(core, capacity))
         .collect::<Result<Vec<_>>>()
 }
 
@@ -372,12 +1331,17 @@ impl Engine {
         platform: &mut Platform<'_>,
         settings: &Settings<Args>,
     ) -> Result<Self> {
+        configure_window(platform, settings);
+
+        let device_info = DeviceInfo::query(core);
+
         // Boilerplate
+        let msaa_samples = device_info.limits.msaa_samples_up_to(settings.msaa_samples);
         let starter_kit = StarterKit::new(
             core.clone(),
             platform,
             watertender::starter_kit::Settings {
-                msaa_samples: settings.msaa_samples as _,
+                msaa_samples: msaa_samples as _,
                 ..Default::default()
             },
         )?;
@@ -385,13 +1349,11 @@ impl Engine {
         // Scene UBO
         let scene_ubo = FrameDataUbo::new(core.clone(), FRAMES_IN_FLIGHT)?;
 
-        // Transforms data
-        // TODO: Auto-resize! (Use a deletion queue)
-        let transforms = create_transform_buffers(core, settings.max_transforms)?;
+        // Transforms data. Grows on demand
+        let transform_capacity = settings.max_transforms.max(1);
+        let transforms = create_transform_buffers(core, transform_capacity)?;
 
         // Create descriptor set layout
-        const FRAME_DATA_BINDING: u32 = 0;
-        const TRANSFORM_BINDING: u32 = 1;
         let bindings = [
             vk::DescriptorSetLayoutBindingBuilder::new()
                 .binding(FRAME_DATA_BINDING)
@@ -467,13 +1429,53 @@ impl Engine {
             }
         }
 
-        let descriptor_set_layouts = [descriptor_set_layout];
+        // Texture descriptor set layout; one set per texture
+        let texture_bindings = [vk::DescriptorSetLayoutBindingBuilder::new()
+            .binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .descriptor_count(1)
+            // Must match `TEXTURE_SHADER_STAGES`, which uploads synchronize with
+            .stage_flags(vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT)];
+
+        let texture_set_layout_ci =
+            vk::DescriptorSetLayoutCreateInfoBuilder::new().bindings(&texture_bindings);
+
+        let texture_set_layout = unsafe {
+            core.device
+                .create_descriptor_set_layout(&texture_set_layout_ci, None, None)
+        }
+        .result()?;
+
+        let texture_pool_sizes = [vk::DescriptorPoolSizeBuilder::new()
+            ._type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .descriptor_count(MAX_TEXTURES)];
+
+        let create_info = vk::DescriptorPoolCreateInfoBuilder::new()
+            .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
+            .pool_sizes(&texture_pool_sizes)
+            .max_sets(MAX_TEXTURES);
+
+        let texture_descriptor_pool =
+            unsafe { core.device.create_descriptor_pool(&create_info, None, None) }.result()?;
+
+        // User buffer descriptor sets, allocated per frame
+        let buffer_sets = BufferSets::new(core)?;
+
+        let descriptor_set_layouts = [
+            descriptor_set_layout,
+            texture_set_layout,
+            buffer_sets.layout(),
+        ];
+
+        // Pipeline layout. Push constants hold the transform index, followed by user uniforms
+        let push_constants_size = (device_info.limits.max_push_constants_size as usize)
+            .min(UNIFORMS_OFFSET + MAX_UNIFORMS_SIZE);
+        let max_uniforms_size = push_constants_size - UNIFORMS_OFFSET;
 
-        // Pipeline layout
         let push_constant_ranges = [vk::PushConstantRangeBuilder::new()
-            .stage_flags(vk::ShaderStageFlags::VERTEX)
+            .stage_flags(PUSH_CONSTANT_STAGES)
             .offset(0)
-            .size(std::mem::size_of::<u32>() as u32)];
+            .size(push_constants_size as u32)];
 
         let create_info = vk::PipelineLayoutCreateInfoBuilder::new()
             .push_constant_ranges(&push_constant_ranges)
@@ -483,76 +1485,211 @@ impl Engine {
             unsafe { core.device.create_pipeline_layout(&create_info, None, None) }.result()?;
 
         let mut shaders = SlotMap::with_key();
+        let default_layout = VertexLayoutInfo::of::<Vertex>();
 
-        let default_shader = shader(
-            core,
-            DEFAULT_VERTEX_SHADER,
-            DEFAULT_FRAGMENT_SHADER,
-            Primitive::Triangles.into(),
-            starter_kit.render_pass,
+        let target = PipelineTarget {
+            render_pass: starter_kit.render_pass,
             pipeline_layout,
-            starter_kit.msaa_samples,
+            msaa_samples: starter_kit.msaa_samples,
+        };
+        let default_shader = create_pipeline(
+            core,
+            &target,
+            &PipelineDesc {
+                vertex: DEFAULT_VERTEX_SHADER,
+                fragment: DEFAULT_FRAGMENT_SHADER,
+                primitive: Primitive::Triangles.into(),
+                layout: &default_layout,
+                settings: &ShaderSettings::default(),
+            },
         )?;
 
-        let default_shader_key = shaders.insert(default_shader);
+        let default_shader_key = shaders.insert(ShaderData {
+            pipeline: default_shader,
+            layout: default_layout.clone(),
+            buffers: BufferBindings::default(),
+        });
 
-        Ok(Self {
+        let default_instanced_shader = create_pipeline(
+            core,
+            &target,
+            &PipelineDesc {
+                vertex: DEFAULT_INSTANCED_VERTEX_SHADER,
+                fragment: DEFAULT_FRAGMENT_SHADER,
+                primitive: Primitive::Triangles.into(),
+                layout: &default_layout,
+                settings: &ShaderSettings::default(),
+            },
+        )?;
+
+        let default_instanced_shader_key = shaders.insert(ShaderData {
+            pipeline: default_instanced_shader,
+            layout: default_layout,
+            buffers: BufferBindings::default(),
+        });
+
+        let mut engine = Self {
             shaders,
             vertex_bufs: SlotMap::with_key(),
+            vertex_layouts: SecondaryMap::new(),
             index_bufs: SlotMap::with_key(),
-            //instance_bufs: SlotMap::with_key(),
-            //textures: SlotMap::with_key(),
+            instance_bufs: SlotMap::with_key(),
+            storage_bufs: SlotMap::with_key(),
+            uniform_bufs: SlotMap::with_key(),
+            textures: SlotMap::with_key(),
             default_shader_key,
+            default_instanced_shader_key,
 
             transforms,
+            transform_capacities: vec![transform_capacity; FRAMES_IN_FLIGHT],
 
             queued_uploads: vec![],
 
+            deletion_queue: vec![],
+            frame_count: 0,
+
             descriptor_sets,
             descriptor_pool,
             descriptor_set_layout,
+            texture_descriptor_pool,
+            texture_set_layout,
+            buffer_sets,
             pipeline_layout,
+            max_uniforms_size,
 
             scene_ubo,
             starter_kit,
 
             camera_prefix: Matrix4::identity(),
+            view: Matrix4::identity(),
+            projection: Matrix4::identity(),
+
+            cursor_position: [0.; 2],
+            last_frame: Instant::now(),
+
+            window_title: WindowTitle::new(settings.name.clone(), settings.show_fps),
+            requested_fullscreen: None,
+
+            background: Background::default(),
+            gradient_shader: None,
+
+            device_info,
+
+            draw_errors: vec![],
+
+            #[cfg(feature = "hot-reload")]
+            shader_watcher: None,
+            #[cfg(feature = "hot-reload")]
+            shader_reload_errors: vec![],
+
+            msaa_samples,
 
             start_time: Instant::now(),
-        })
+        };
+
+        engine.set_background(settings.background)?;
+
+        Ok(engine)
     }
 
     fn frame(
         &mut self,
-        packet: Vec<DrawCmd>,
+        mut packet: Vec<DrawCmd>,
         frame: Frame,
         core: &SharedCore,
         platform: &mut Platform,
     ) -> Result<PlatformReturn> {
+        // Skip invalid draws before recording begins, reporting them to the app instead of
+        // failing the frame
+        let mut draw_errors = vec![];
+        packet = packet
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, cmd)| match self.validate_draw(&cmd) {
+                Ok(()) => Some(cmd),
+                Err(error) => {
+                    draw_errors.push(DrawError { index, error });
+                    None
+                }
+            })
+            .collect();
+        self.draw_errors = draw_errors;
+
+        if let Some(background) = self.background_draw() {
+            packet.insert(0, background);
+        }
+
         let cmd = self.starter_kit.begin_command_buffer(&frame)?;
         let command_buffer = cmd.command_buffer;
 
+        // Frames which might have used freed resources are finished by now
+        self.collect_garbage();
+        self.frame_count += 1;
+        self.buffer_sets
+            .reset(&self.starter_kit.core, self.starter_kit.frame)?;
+
+        #[cfg(feature = "hot-reload")]
+        self.reload_changed_shaders();
+
+        // Gather transforms. Index 0 is reserved for the default (identity) transform
+        let mut transforms = vec![TRANSFORM_IDENTITY];
+        let transform_indices: Vec<u32> = packet
+            .iter()
+            .map(|cmd| match cmd.transform {
+                Some(transform) => {
+                    transforms.push(transform);
+                    (transforms.len() - 1) as u32
+                }
+                None => 0,
+            })
+            .collect();
+
+        // Write transforms data. This must happen before the descriptor set is bound, as the
+        // transform buffer may need to be reallocated
+        self.reserve_transforms(transforms.len())?;
+        self.transforms[self.starter_kit.frame]
+            .write_bytes(0, bytemuck::cast_slice(&transforms))?;
+
         unsafe {
-            // Upload buffers
-            for job in self.queued_uploads.drain(..) {
+            // The GPU-side buffers are shared between frames, so earlier frames must be done
+            // reading them before they are overwritten
+            let buf_read_mem_barrier = vk::MemoryBarrierBuilder::new()
+                .src_access_mask(
+                    vk::AccessFlags::SHADER_READ
+                        | vk::AccessFlags::SHADER_WRITE
+                        | vk::AccessFlags::UNIFORM_READ
+                        | vk::AccessFlags::VERTEX_ATTRIBUTE_READ
+                        | vk::AccessFlags::INDEX_READ,
+                )
+                .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE);
+
+            core.device.cmd_pipeline_barrier(
+                command_buffer,
+                vk::PipelineStageFlags::ALL_GRAPHICS,
+                vk::PipelineStageFlags::TRANSFER,
+                None,
+                &[buf_read_mem_barrier],
+                &[],
+                &[],
+            );
+
+            // Upload buffers (skipping any which were freed since)
+            for job in std::mem::take(&mut self.queued_uploads) {
                 match job {
-                    QueuedUpload::VertexBuffer(key) => {
-                        let memory = self.vertex_bufs.get(key).unwrap();
-                        write_cpu_gpu_copy(
-                            &self.starter_kit.core,
-                            command_buffer,
-                            memory,
-                            self.starter_kit.frame,
-                        );
-                    }
-                    QueuedUpload::IndexBuffer(key) => {
-                        let memory = self.index_bufs.get(key).unwrap();
-                        write_cpu_gpu_copy(
-                            &self.starter_kit.core,
-                            command_buffer,
-                            memory,
-                            self.starter_kit.frame,
-                        );
+                    QueuedUpload::VertexBuffer(key) => self.upload_buffer(command_buffer, key),
+                    QueuedUpload::IndexBuffer(key) => self.upload_buffer(command_buffer, key),
+                    QueuedUpload::InstanceBuffer(key) => self.upload_buffer(command_buffer, key),
+                    QueuedUpload::StorageBuffer(key) => self.upload_buffer(command_buffer, key),
+                    QueuedUpload::UniformBuffer(key) => self.upload_buffer(command_buffer, key),
+                    QueuedUpload::Texture(key) => {
+                        if let Some(texture) = self.textures.get(key) {
+                            write_cpu_gpu_image_copy(
+                                &self.starter_kit.core,
+                                command_buffer,
+                                texture,
+                                self.starter_kit.frame,
+                            );
+                        }
                     }
                 }
             }
@@ -560,7 +1697,12 @@ impl Engine {
             // Make sure buffer uploads are synchronized
             let buf_upload_mem_barrier = vk::MemoryBarrierBuilder::new()
                 .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
-                .dst_access_mask(vk::AccessFlags::SHADER_READ);
+                .dst_access_mask(
+                    vk::AccessFlags::SHADER_READ
+                        | vk::AccessFlags::UNIFORM_READ
+                        | vk::AccessFlags::VERTEX_ATTRIBUTE_READ
+                        | vk::AccessFlags::INDEX_READ,
+                );
 
             core.device.cmd_pipeline_barrier(
                 command_buffer,
@@ -575,6 +1717,25 @@ impl Engine {
             self.starter_kit.begin_render_pass(&frame);
             self.starter_kit.set_viewport();
 
+            // The render pass always clears to the same color, so clear again to the chosen one
+            if let Background::Color(color) = self.background {
+                let attachments = [vk::ClearAttachmentBuilder::new()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .color_attachment(0)
+                    .clear_value(vk::ClearValue {
+                        color: vk::ClearColorValue { float32: color },
+                    })];
+                let rects = [vk::ClearRectBuilder::new()
+                    .rect(vk::Rect2D {
+                        offset: vk::Offset2D { x: 0, y: 0 },
+                        extent: self.starter_kit.framebuffer.extent(),
+                    })
+                    .base_array_layer(0)
+                    .layer_count(1)];
+                core.device
+                    .cmd_clear_attachments(command_buffer, &attachments, &rects);
+            }
+
             // Bind UBO
             core.device.cmd_bind_descriptor_sets(
                 command_buffer,
@@ -585,84 +1746,163 @@ impl Engine {
                 &[],
             );
 
-            let mut transforms = vec![TRANSFORM_IDENTITY];
-
             // Draw frame packet
-            for cmd in packet {
+            for (cmd, transform_index) in packet.into_iter().zip(transform_indices) {
                 // Bind current shader, or default if None
+                let default_shader = match cmd.instances {
+                    Some(_) => self.default_instanced_shader_key,
+                    None => self.default_shader_key,
+                };
+                let shader = self
+                    .shaders
+                    .get(cmd.shader.unwrap_or(default_shader))
+                    .ok_or(Error::InvalidHandle(ResourceKind::Shader))?;
                 core.device.cmd_bind_pipeline(
                     command_buffer,
                     vk::PipelineBindPoint::GRAPHICS,
-                    *self
-                        .shaders
-                        .get(cmd.shader.unwrap_or(self.default_shader_key))
-                        .unwrap(),
+                    shader.pipeline,
                 );
 
-                // Add transform to the buffer if present; otherwise use the default (identity) transform.
-                let transform_index;
-                match cmd.transform {
-                    Some(transform) => {
-                        transform_index = transforms.len() as u32;
-                        transforms.push(transform);
-                    }
-                    None => transform_index = 0,
-                };
-
                 // Transform index is conveyed via push constant
                 let push_const = [transform_index];
                 core.device.cmd_push_constants(
                     command_buffer,
                     self.pipeline_layout,
-                    vk::ShaderStageFlags::VERTEX,
+                    PUSH_CONSTANT_STAGES,
                     0,
                     std::mem::size_of_val(&push_const) as u32,
                     push_const.as_ptr() as _,
                 );
 
-                // Bind vertex buffers
-                let vertex_memory = self.vertex_bufs.get(cmd.vertices).unwrap();
-                core.device.cmd_bind_vertex_buffers(
-                    command_buffer,
-                    0,
-                    &[vertex_memory.gpu.buffer()],
-                    &[0],
-                );
+                // User uniforms follow the transform index
+                if let Some(uniforms) = &cmd.uniforms {
+                    let bytes = uniforms.padded_bytes();
+                    if !bytes.is_empty() {
+                        core.device.cmd_push_constants(
+                            command_buffer,
+                            self.pipeline_layout,
+                            PUSH_CONSTANT_STAGES,
+                            UNIFORMS_OFFSET as u32,
+                            bytes.len() as u32,
+                            bytes.as_ptr() as _,
+                        );
+                    }
+                }
+
+                // Bind texture, if any
+                if let Some(texture) = cmd.texture {
+                    let texture = self
+                        .textures
+                        .get(texture)
+                        .ok_or(Error::InvalidHandle(ResourceKind::Texture))?;
+                    core.device.cmd_bind_descriptor_sets(
+                        command_buffer,
+                        vk::PipelineBindPoint::GRAPHICS,
+                        self.pipeline_layout,
+                        1,
+                        &[texture.descriptor_set],
+                        &[],
+                    );
+                }
+
+                // Bind user buffers, if any
+                let buffers = shader.buffers.overridden_by(&cmd.buffers);
+                if !buffers.is_empty() {
+                    let storage = buffers
+                        .storage
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(slot, key)| {
+                            let memory = self.storage_bufs.get((*key)?)?;
+                            Some((slot as u32, memory.gpu.buffer(), memory.size_bytes))
+                        });
+                    let uniform = buffers
+                        .uniform
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(slot, key)| {
+                            let memory = self.uniform_bufs.get((*key)?)?;
+                            let binding = (STORAGE_BUFFER_SLOTS + slot) as u32;
+                            Some((binding, memory.gpu.buffer(), memory.size_bytes))
+                        });
+                    let infos: Vec<_> = storage.chain(uniform).collect();
+
+                    let set =
+                        self.buffer_sets
+                            .get(core, self.starter_kit.frame, &buffers, &infos)?;
+                    core.device.cmd_bind_descriptor_sets(
+                        command_buffer,
+                        vk::PipelineBindPoint::GRAPHICS,
+                        self.pipeline_layout,
+                        BUFFER_SET,
+                        &[set],
+                        &[],
+                    );
+                }
+
+                // Bind vertex buffer, if any
+                let n_vertices = match cmd.vertices {
+                    Some(vertices) => {
+                        let vertex_memory = self
+                            .vertex_bufs
+                            .get(vertices)
+                            .ok_or(Error::InvalidHandle(ResourceKind::VertexBuffer))?;
+                        core.device.cmd_bind_vertex_buffers(
+                            command_buffer,
+                            VERTEX_BINDING,
+                            &[vertex_memory.gpu.buffer()],
+                            &[0],
+                        );
+                        cmd.count(Some(vertex_memory.length))
+                    }
+                    None => cmd.count(None),
+                };
+
+                // Bind instance buffer, if any. Otherwise draw a single instance
+                let n_instances = match cmd.instances {
+                    Some(instances) => {
+                        let instance_memory = self
+                            .instance_bufs
+                            .get(instances)
+                            .ok_or(Error::InvalidHandle(ResourceKind::InstanceBuffer))?;
+                        core.device.cmd_bind_vertex_buffers(
+                            command_buffer,
+                            INSTANCE_BINDING,
+                            &[instance_memory.gpu.buffer()],
+                            &[0],
+                        );
+                        instance_memory.length
+                    }
+                    None => 1,
+                };
 
                 // Draw indexed if there are indices, otherwise draw only by vertex order
                 if let Some(indices) = cmd.indices {
-                    let index_memory = self.index_bufs.get(indices).unwrap();
+                    let index_memory = self
+                        .index_bufs
+                        .get(indices)
+                        .ok_or(Error::InvalidHandle(ResourceKind::IndexBuffer))?;
                     core.device.cmd_bind_index_buffer(
                         command_buffer,
                         index_memory.gpu.buffer(),
                         0,
-                        vk::IndexType::UINT32,
+                        index_memory.index_type,
                     );
 
-                    let n_indices = cmd
-                        .limit
-                        .map(|limit| index_memory.length.min(limit))
-                        .unwrap_or(index_memory.length);
-                    core.device
-                        .cmd_draw_indexed(command_buffer, n_indices, 1, 0, 0, 0)
+                    let n_indices = cmd.count(Some(index_memory.length));
+                    core.device.cmd_draw_indexed(
+                        command_buffer,
+                        n_indices,
+                        n_instances,
+                        cmd.first,
+                        cmd.base_vertex,
+                        0,
+                    )
                 } else {
-                    let n_vertices = cmd
-                        .limit
-                        .map(|limit| vertex_memory.length.min(limit))
-                        .unwrap_or(vertex_memory.length);
-                    core.device.cmd_draw(command_buffer, n_vertices, 1, 0, 0);
+                    core.device
+                        .cmd_draw(command_buffer, n_vertices, n_instances, cmd.first, 0);
                 }
             }
-
-            // Write transforms data
-            let bytes = bytemuck::cast_slice(&transforms);
-            let frame = self.starter_kit.frame;
-            let buffer = &mut self.transforms[frame];
-            ensure!(
-                (bytes.len() as u64) < buffer.memory.as_ref().unwrap().size(),
-                "Maximum transforms exceeded"
-            );
-            buffer.write_bytes(0, bytes)?;
         }
 
         let (ret, cameras) = watertender::multi_platform_camera::platform_camera_prefix(
@@ -670,11 +1910,55 @@ impl Engine {
             self.camera_prefix,
         )?;
 
+        // The headset's view and projection matrices are only available combined
+        let (view, projection) = match platform.is_vr() {
+            true => {
+                let eye = |i: usize| Matrix4::from_column_slice(&cameras[i * 16..(i + 1) * 16]);
+                ([eye(0), eye(1)], [Matrix4::identity(); 2])
+            }
+            false => ([self.view; 2], [self.projection; 2]),
+        };
+
+        let columns = |m: &Matrix4<f32>| {
+            let mut out = [0.; 16];
+            out.copy_from_slice(m.as_slice());
+            out
+        };
+        let per_view =
+            |matrices: &[Matrix4<f32>; 2]| [columns(&matrices[0]), columns(&matrices[1])];
+        let inverse = |matrices: &[Matrix4<f32>; 2]| {
+            let inv = |m: &Matrix4<f32>| m.try_inverse().unwrap_or_else(Matrix4::identity);
+            per_view(&[inv(&matrices[0]), inv(&matrices[1])])
+        };
+
+        let now = Instant::now();
+        let delta_time = (now - self.last_frame).as_secs_f32();
+        self.last_frame = now;
+
+        self.window_title.frame(platform);
+        if let Some(fullscreen) = self.requested_fullscreen.take() {
+            if let Platform::Winit { window, .. } = platform {
+                window.set_fullscreen(fullscreen_mode(fullscreen));
+            }
+        }
+
+        let (width, height) = self.screen_size();
+
         self.scene_ubo.upload(
             self.starter_kit.frame,
             &SceneData {
                 cameras,
                 time: self.start_time.elapsed().as_secs_f32(),
+                _pad: [0.; 3],
+                view: per_view(&view),
+                projection: per_view(&projection),
+                inverse_view: inverse(&view),
+                inverse_projection: inverse(&projection),
+                resolution: [width as f32, height as f32],
+                delta_time,
+                // The count already includes this frame
+                frame_index: (self.frame_count - 1) as u32,
+                cursor: self.cursor_position,
             },
         )?;
 
@@ -684,6 +1968,379 @@ impl Engine {
         Ok(ret)
     }
 
+    /// Check that every handle referenced by the draw command is valid
+    fn validate_draw(&self, cmd: &DrawCmd) -> std::result::Result<(), Error> {
+        if let Some(error) = cmd.slot_error {
+            return Err(error);
+        }
+        let check = |valid: bool, kind| match valid {
+            true => Ok(()),
+            false => Err(Error::InvalidHandle(kind)),
+        };
+        match (cmd.vertices, cmd.shader) {
+            (Some(vertices), _) => check(
+                self.vertex_bufs.contains_key(vertices),
+                ResourceKind::VertexBuffer,
+            )?,
+            (None, None) => return Err(Error::MissingVertices),
+            (None, Some(_)) => (),
+        }
+        if let Some(indices) = cmd.indices {
+            check(
+                self.index_bufs.contains_key(indices),
+                ResourceKind::IndexBuffer,
+            )?;
+        }
+        if let Some(instances) = cmd.instances {
+            check(
+                self.instance_bufs.contains_key(instances),
+                ResourceKind::InstanceBuffer,
+            )?;
+        }
+        if let Some(shader) = cmd.shader {
+            check(self.shaders.contains_key(shader), ResourceKind::Shader)?;
+        }
+
+        // The default shaders share a vertex type and attached buffers, so either will do
+        let shader = &self.shaders[cmd.shader.unwrap_or(self.default_shader_key)];
+
+        let buffers = shader.buffers.overridden_by(&cmd.buffers);
+        for &buffer in buffers.storage.iter().flatten() {
+            check(
+                self.storage_bufs.contains_key(buffer),
+                ResourceKind::StorageBuffer,
+            )?;
+        }
+        for &buffer in buffers.uniform.iter().flatten() {
+            check(
+                self.uniform_bufs.contains_key(buffer),
+                ResourceKind::UniformBuffer,
+            )?;
+        }
+
+        // Draws without vertices may use any shader which does not read vertex attributes
+        match cmd.vertices {
+            Some(vertices) => {
+                let vertices = self.vertex_layouts[vertices];
+                if shader.layout.id != vertices {
+                    return Err(Error::LayoutMismatch {
+                        expected: shader.layout.id.name,
+                        actual: vertices.name,
+                    });
+                }
+            }
+            None if !shader.layout.attributes.is_empty() => {
+                return Err(Error::LayoutMismatch {
+                    expected: shader.layout.id.name,
+                    actual: LayoutId::of::<()>().name,
+                });
+            }
+            None => (),
+        }
+        if let Some(texture) = cmd.texture {
+            check(self.textures.contains_key(texture), ResourceKind::Texture)?;
+        }
+        if let Some(uniforms) = &cmd.uniforms {
+            if uniforms.len() > self.max_uniforms_size {
+                return Err(Error::UniformsTooLarge {
+                    size: uniforms.len(),
+                    max: self.max_uniforms_size,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the vertex buffer was created with the vertex type `V`
+    fn check_vertex_layout<V: VertexLayout>(
+        &self,
+        handle: VertexBuffer,
+    ) -> std::result::Result<(), Error> {
+        let layout = *self
+            .vertex_layouts
+            .get(handle)
+            .ok_or(Error::InvalidHandle(ResourceKind::VertexBuffer))?;
+        let expected = LayoutId::of::<V>();
+        match layout == expected {
+            true => Ok(()),
+            false => Err(Error::LayoutMismatch {
+                expected: layout.name,
+                actual: expected.name,
+            }),
+        }
+    }
+
+    /// Check that the index buffer was created with the index type `I`
+    fn check_index_type<I: Index>(&self, handle: IndexBuffer) -> std::result::Result<(), Error> {
+        let index_type = self
+            .index_bufs
+            .get(handle)
+            .ok_or(Error::InvalidHandle(ResourceKind::IndexBuffer))?
+            .index_type;
+        match index_type == I::index_type() {
+            true => Ok(()),
+            false => Err(Error::IndexTypeMismatch {
+                expected: index_type_name(index_type),
+                actual: index_type_name(I::index_type()),
+            }),
+        }
+    }
+
+    /// Create a buffer holding `data`, which contains `length` elements, and queue its upload
+    fn create_buffer<K: BufferKey>(
+        &mut self,
+        data: &[u8],
+        length: usize,
+        dynamic: bool,
+    ) -> Result<K> {
+        self.check_buffer_size::<K>(data.len())?;
+        let memory = SyncMemory::new(&self.starter_kit.core, data, length, K::USAGE, dynamic)?;
+        let key = K::buffers(self).insert(memory);
+        self.queued_uploads.push(key.upload());
+        Ok(key)
+    }
+
+    /// Replace the contents of a buffer with `data`, which contains `length` elements, and queue
+    /// its upload
+    fn write_buffer<K: BufferKey>(&mut self, handle: K, data: &[u8], length: usize) -> Result<()> {
+        self.check_buffer_size::<K>(data.len())?;
+        let core = self.starter_kit.core.clone();
+        let frame = self.starter_kit.frame;
+        let memory = K::buffers(self)
+            .get_mut(handle)
+            .ok_or(Error::InvalidHandle(K::KIND))?;
+        if let Some(retired) = memory.write(&core, frame, data, length, K::KIND)? {
+            self.defer_deletion(Garbage::Buffer(retired));
+        }
+        self.queued_uploads.push(handle.upload());
+        Ok(())
+    }
+
+    /// Write `data` into a buffer at `offset` bytes, and queue the upload of the written range
+    fn write_buffer_range<K: BufferKey>(
+        &mut self,
+        handle: K,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<()> {
+        let frame = self.starter_kit.frame;
+        K::buffers(self)
+            .get_mut(handle)
+            .ok_or(Error::InvalidHandle(K::KIND))?
+            .write_range(frame, offset as u64, data, K::KIND)?;
+        self.queued_uploads.push(handle.upload());
+        Ok(())
+    }
+
+    /// Free a buffer once frames in flight are done with it
+    fn free_buffer<K: BufferKey>(&mut self, handle: K) -> Result<()> {
+        let memory = K::buffers(self)
+            .remove(handle)
+            .ok_or(Error::InvalidHandle(K::KIND))?;
+        self.defer_deletion(Garbage::Buffer(memory));
+        Ok(())
+    }
+
+    /// Record the upload of a buffer, unless it was freed since the upload was queued
+    fn upload_buffer<K: BufferKey>(&mut self, command_buffer: CommandBuffer, handle: K) {
+        let core = self.starter_kit.core.clone();
+        let frame = self.starter_kit.frame;
+        if let Some(memory) = K::buffers(self).get_mut(handle) {
+            write_cpu_gpu_copy(&core, command_buffer, memory, frame);
+        }
+    }
+
+    /// Check that a buffer of `size` bytes can be bound to a descriptor in its entirety
+    fn check_buffer_size<K: BufferKey>(&self, size: usize) -> std::result::Result<(), Error> {
+        let limits = &self.device_info.limits;
+        let max = match K::KIND {
+            ResourceKind::StorageBuffer => limits.max_storage_buffer_range,
+            ResourceKind::UniformBuffer => limits.max_uniform_buffer_range,
+            _ => return Ok(()),
+        };
+        match size <= max as usize {
+            true => Ok(()),
+            false => Err(Error::BufferTooLarge {
+                kind: K::KIND,
+                size,
+                max: max as usize,
+            }),
+        }
+    }
+
+    /// Check that a texture has pixels, and fits within the device's image size limit
+    fn check_texture_size(&self, width: usize, height: usize) -> std::result::Result<(), Error> {
+        let max = self.device_info.limits.max_texture_size as usize;
+        match (1..=max).contains(&width) && (1..=max).contains(&height) {
+            true => Ok(()),
+            false => Err(Error::InvalidTextureSize { width, height, max }),
+        }
+    }
+
+    /// Make sure the transform buffer for the current frame can hold at least `required`
+    /// transforms, reallocating it and updating the frame's descriptor set if not. Must be called
+    /// after the current frame's fence has been waited on, and before the descriptor set is bound.
+    fn reserve_transforms(&mut self, required: usize) -> Result<()> {
+        let frame = self.starter_kit.frame;
+        let capacity = self.transform_capacities[frame];
+        if required <= capacity {
+            return Ok(());
+        }
+
+        let capacity = required.max(capacity * 2);
+        let buffer = create_transform_buffer(&self.starter_kit.core, capacity)?;
+
+        let transform_bi = [vk::DescriptorBufferInfoBuilder::new()
+            .buffer(buffer.buffer())
+            .offset(0)
+            .range(vk::WHOLE_SIZE)];
+
+        let writes = [vk::WriteDescriptorSetBuilder::new()
+            .buffer_info(&transform_bi)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .dst_set(self.descriptor_sets[frame])
+            .dst_binding(TRANSFORM_BINDING)
+            .dst_array_element(0)];
+
+        unsafe {
+            self.starter_kit
+                .core
+                .device
+                .update_descriptor_sets(&writes, &[]);
+        }
+
+        let old = std::mem::replace(&mut self.transforms[frame], buffer);
+        self.transform_capacities[frame] = capacity;
+        self.defer_deletion(Garbage::TransformBuffer(old));
+
+        Ok(())
+    }
+
+    /// Create a pipeline compatible with the engine's render pass and layout
+    fn build_pipeline(
+        &self,
+        vertex: &[u8],
+        fragment: &[u8],
+        primitive: vk::PrimitiveTopology,
+        layout: &VertexLayoutInfo,
+        settings: &ShaderSettings,
+    ) -> Result<vk::Pipeline> {
+        // Watertender creates the logical device without enabling fillModeNonSolid, so
+        // support on the physical device is not enough to use it
+        if settings.polygon_mode != PolygonMode::Fill {
+            return Err(Error::Unsupported("fillModeNonSolid").into());
+        }
+
+        let target = PipelineTarget {
+            render_pass: self.starter_kit.render_pass,
+            pipeline_layout: self.pipeline_layout,
+            msaa_samples: self.starter_kit.msaa_samples,
+        };
+        create_pipeline(
+            &self.starter_kit.core,
+            &target,
+            &PipelineDesc {
+                vertex,
+                fragment,
+                primitive,
+                layout,
+                settings,
+            },
+        )
+    }
+
+    /// Create a pipeline from shader files on disk
+    fn load_pipeline(
+        &self,
+        vertex: &Path,
+        fragment: &Path,
+        primitive: vk::PrimitiveTopology,
+        layout: &VertexLayoutInfo,
+        settings: &ShaderSettings,
+    ) -> Result<vk::Pipeline> {
+        let vertex = load_shader_file(vertex, Stage::Vertex)?;
+        let fragment = load_shader_file(fragment, Stage::Fragment)?;
+        self.build_pipeline(&vertex, &fragment, primitive, layout, settings)
+    }
+
+    /// Rebuild shaders whose files have changed on disk. Failures are kept for the app to take, and
+    /// the previous pipeline is kept.
+    #[cfg(feature = "hot-reload")]
+    fn reload_changed_shaders(&mut self) {
+        let changed = match &mut self.shader_watcher {
+            Some(watcher) => watcher.changed(&mut self.shader_reload_errors),
+            None => return,
+        };
+
+        for (handle, tracked) in changed {
+            let layout = match self.shaders.get(handle) {
+                Some(shader) => shader.layout.clone(),
+                None => continue,
+            };
+
+            let pipeline = self.load_pipeline(
+                &tracked.vertex,
+                &tracked.fragment,
+                tracked.primitive,
+                &layout,
+                &tracked.settings,
+            );
+
+            match pipeline {
+                Ok(pipeline) => {
+                    let shader = &mut self.shaders[handle];
+                    let old = std::mem::replace(&mut shader.pipeline, pipeline);
+                    self.defer_deletion(Garbage::Pipeline(old));
+                }
+                Err(e) => self.shader_reload_errors.push(e.context(format!(
+                    "Failed to reload shader ({}, {})",
+                    tracked.vertex.display(),
+                    tracked.fragment.display()
+                ))),
+            }
+        }
+    }
+
+    /// Queue a resource for deletion once no frame in flight can be using it
+    fn defer_deletion(&mut self, garbage: Garbage) {
+        self.deletion_queue.push((self.frame_count, garbage));
+    }
+
+    /// Destroy resources freed at least `FRAMES_IN_FLIGHT` frames ago. Must be called after the
+    /// current frame's fence has been waited on.
+    fn collect_garbage(&mut self) {
+        let frame_count = self.frame_count;
+        let (expired, remaining): (Vec<_>, Vec<_>) = std::mem::take(&mut self.deletion_queue)
+            .into_iter()
+            .partition(|(freed_on, _)| frame_count >= freed_on + FRAMES_IN_FLIGHT as u64);
+        self.deletion_queue = remaining;
+
+        for (_, garbage) in expired {
+            unsafe { garbage.destroy(&self.starter_kit.core, self.texture_descriptor_pool) }
+        }
+    }
+
+    /// Draw covering the screen with the background, drawn before the frame packet
+    fn background_draw(&self) -> Option<DrawCmd> {
+        match (self.background, self.gradient_shader) {
+            (Background::Gradient { top, bottom }, Some(shader)) => {
+                Some(DrawCmd::fullscreen(shader).uniforms_pod(&[top, bottom]))
+            }
+            _ => None,
+        }
+    }
+
+    /// Track state reported to shaders from platform events
+    fn event(&mut self, event: &PlatformEvent<'_, '_>) {
+        if let PlatformEvent::Winit(crate::winit::event::Event::WindowEvent {
+            event: crate::winit::event::WindowEvent::CursorMoved { position, .. },
+            ..
+        }) = event
+        {
+            self.cursor_position = [position.x as f32, position.y as f32];
+        }
+    }
+
     fn swapchain_resize(&mut self, images: Vec<vk::Image>, extent: vk::Extent2D) -> Result<()> {
         self.starter_kit.swapchain_resize(images, extent)
     }
@@ -693,27 +2350,133 @@ impl Engine {
     }
 }
 
+/// Record a copy of each range written since the last upload from the CPU-side memory to the
+/// GPU-side memory
 fn write_cpu_gpu_copy(
     core: &Core,
     command_buffer: CommandBuffer,
-    memory: &SyncMemory,
+    memory: &mut SyncMemory,
     frame: usize,
 ) {
-    let region = vk::BufferCopyBuilder::new()
-        .size(memory.size_bytes)
-        .src_offset(0)
-        .dst_offset(0);
+    let regions: Vec<_> = memory
+        .take_dirty_ranges()
+        .into_iter()
+        // Zero-sized copies are not allowed
+        .filter(|range| range.end > range.start)
+        .map(|range| {
+            vk::BufferCopyBuilder::new()
+                .size(range.end - range.start)
+                .src_offset(range.start)
+                .dst_offset(range.start)
+        })
+        .collect();
+
+    if regions.is_empty() {
+        return;
+    }
 
     unsafe {
         core.device.cmd_copy_buffer(
             command_buffer,
             memory.cpu.buffer(frame),
             memory.gpu.buffer(),
-            &[region],
+            &regions,
+        )
+    }
+}
+
+/// Record a copy from the CPU-side memory of a texture to its image, transitioning the image
+/// from whatever layout it was in to `SHADER_READ_ONLY_OPTIMAL`.
+fn write_cpu_gpu_image_copy(
+    core: &Core,
+    command_buffer: CommandBuffer,
+    texture: &TextureData,
+    frame: usize,
+) {
+    // The entire image is overwritten, so previous contents may be discarded. Earlier frames may
+    // still be sampling the image, so wait for the shaders to finish.
+    let to_transfer = vk::ImageMemoryBarrierBuilder::new()
+        .old_layout(vk::ImageLayout::UNDEFINED)
+        .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(texture.image.instance())
+        .subresource_range(color_subresource_range())
+        .src_access_mask(vk::AccessFlags::empty())
+        .dst_access_mask(vk::AccessFlags::TRANSFER_WRITE);
+
+    let region = vk::BufferImageCopyBuilder::new()
+        .buffer_offset(0)
+        .buffer_row_length(0)
+        .buffer_image_height(0)
+        .image_subresource(
+            vk::ImageSubresourceLayersBuilder::new()
+                .aspect_mask(vk::ImageAspectFlags::COLOR)
+                .mip_level(0)
+                .base_array_layer(0)
+                .layer_count(1)
+                .build(),
         )
+        .image_offset(vk::Offset3D { x: 0, y: 0, z: 0 })
+        .image_extent(texture.extent);
+
+    let to_shader = vk::ImageMemoryBarrierBuilder::new()
+        .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(texture.image.instance())
+        .subresource_range(color_subresource_range())
+        .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+        .dst_access_mask(vk::AccessFlags::SHADER_READ);
+
+    unsafe {
+        core.device.cmd_pipeline_barrier(
+            command_buffer,
+            TEXTURE_SHADER_STAGES,
+            vk::PipelineStageFlags::TRANSFER,
+            None,
+            &[],
+            &[],
+            &[to_transfer],
+        );
+
+        core.device.cmd_copy_buffer_to_image(
+            command_buffer,
+            texture.cpu.buffer(frame),
+            texture.image.instance(),
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &[region],
+        );
+
+        core.device.cmd_pipeline_barrier(
+            command_buffer,
+            vk::PipelineStageFlags::TRANSFER,
+            TEXTURE_SHADER_STAGES,
+            None,
+            &[],
+            &[],
+            &[to_shader],
+        );
     }
 }
 
+/// Shader stages which may sample textures, matching the stages of the texture set layout
+const TEXTURE_SHADER_STAGES: vk::PipelineStageFlags = vk::PipelineStageFlags::from_bits_truncate(
+    vk::PipelineStageFlags::VERTEX_SHADER.bits() | vk::PipelineStageFlags::FRAGMENT_SHADER.bits(),
+);
+
+/// Subresource range covering the single mip level and layer of a color image
+fn color_subresource_range() -> vk::ImageSubresourceRange {
+    vk::ImageSubresourceRangeBuilder::new()
+        .aspect_mask(vk::ImageAspectFlags::COLOR)
+        .base_mip_level(0)
+        .level_count(1)
+        .base_array_layer(0)
+        .layer_count(1)
+        .build()
+}
+
 impl Drop for Engine {
     fn drop(&mut self) {
         unsafe {
@@ -721,10 +2484,64 @@ impl Drop for Engine {
             core.device
                 .device_wait_idle()
                 .expect("Failed to idle the device");
+            let garbage = self
+                .deletion_queue
+                .drain(..)
+                .map(|(_, garbage)| garbage)
+                .chain(self.textures.drain().map(|(_, t)| Garbage::Texture(t)))
+                .chain(
+                    self.shaders
+                        .drain()
+                        .map(|(_, shader)| Garbage::Pipeline(shader.pipeline)),
+                );
+            for garbage in garbage {
+                garbage.destroy(core, self.texture_descriptor_pool);
+            }
+            core.device
+                .destroy_pipeline_layout(Some(self.pipeline_layout), None);
             core.device
                 .destroy_descriptor_pool(Some(self.descriptor_pool), None);
             core.device
                 .destroy_descriptor_set_layout(Some(self.descriptor_set_layout), None);
+            core.device
+                .destroy_descriptor_pool(Some(self.texture_descriptor_pool), None);
+            core.device
+                .destroy_descriptor_set_layout(Some(self.texture_set_layout), None);
+            self.buffer_sets.destroy(core);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::merge_ranges;
+
+    #[test]
+    fn merge_ranges_empty() {
+        assert!(merge_ranges(vec![]).is_empty());
+    }
+
+    #[test]
+    fn merge_ranges_disjoint() {
+        assert_eq!(merge_ranges(vec![0..4, 8..12]), vec![0..4, 8..12]);
+    }
+
+    #[test]
+    fn merge_ranges_overlapping() {
+        assert_eq!(merge_ranges(vec![0..8, 4..12]), vec![0..12]);
+        assert_eq!(merge_ranges(vec![0..12, 4..8]), vec![0..12]);
+    }
+
+    #[test]
+    fn merge_ranges_adjacent() {
+        assert_eq!(merge_ranges(vec![0..4, 4..8]), vec![0..8]);
+    }
+
+    #[test]
+    fn merge_ranges_unsorted() {
+        assert_eq!(
+            merge_ranges(vec![16..20, 0..4, 6..8, 2..6]),
+            vec![0..8, 16..20]
+        );
+    }
+}
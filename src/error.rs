@@ -0,0 +1,175 @@
+use std::fmt;
+
+/// Kinds of resources which are referred to by handles
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+    VertexBuffer,
+    IndexBuffer,
+    InstanceBuffer,
+    StorageBuffer,
+    UniformBuffer,
+    Shader,
+    Texture,
+}
+
+/// Errors caused by misuse of the Context or invalid Settings. These are returned within `anyhow::Error`, and may be
+/// recovered with `downcast_ref::<idek::Error>()`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The handle was freed, or never belonged to this Context
+    InvalidHandle(ResourceKind),
+    /// Attempted to update a resource which was not created as dynamic
+    NotDynamic(ResourceKind),
+    /// The data supplied does not match the size of the resource (in bytes)
+    SizeMismatch {
+        kind: ResourceKind,
+        expected: usize,
+        actual: usize,
+    },
+    /// A partial update extends past the end of the resource (in bytes)
+    OutOfBounds {
+        kind: ResourceKind,
+        end: usize,
+        size: usize,
+    },
+    /// Built-in resources cannot be freed
+    BuiltIn(ResourceKind),
+    /// The buffer slot does not exist; there are `slots` slots of this kind
+    InvalidSlot {
+        kind: ResourceKind,
+        slot: usize,
+        slots: usize,
+    },
+    /// A draw's uniform data exceeds the push constant space available (in bytes)
+    UniformsTooLarge { size: usize, max: usize },
+    /// Draws without a vertex buffer must specify a shader, as the default shaders read vertices
+    MissingVertices,
+    /// Index data does not match the index type the buffer was created with
+    IndexTypeMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+    /// A storage or uniform buffer is larger than the device can bind at once (in bytes)
+    BufferTooLarge {
+        kind: ResourceKind,
+        size: usize,
+        max: usize,
+    },
+    /// Texture dimensions are zero, or larger than the device supports (in pixels)
+    InvalidTextureSize {
+        width: usize,
+        height: usize,
+        max: usize,
+    },
+    /// A feature required by the shader settings is not enabled on the device
+    Unsupported(&'static str),
+    /// `Settings::msaa_samples` is not a power of two up to 16
+    InvalidMsaaSamples(u8),
+    /// Vertex data does not match the vertex type expected by the shader or buffer
+    LayoutMismatch {
+        expected: &'static str,
+        actual: &'static str,
+    },
+}
+
+/// A draw which was skipped because it was invalid, see `Context::last_frame_errors`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DrawError {
+    /// Index of the draw in the packet returned by `App::frame`
+    pub index: usize,
+    pub error: Error,
+}
+
+impl fmt::Display for DrawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Draw {} skipped: {}", self.index, self.error)
+    }
+}
+
+impl fmt::Display for ResourceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ResourceKind::VertexBuffer => "vertex buffer",
+            ResourceKind::IndexBuffer => "index buffer",
+            ResourceKind::InstanceBuffer => "instance buffer",
+            ResourceKind::StorageBuffer => "storage buffer",
+            ResourceKind::UniformBuffer => "uniform buffer",
+            ResourceKind::Shader => "shader",
+            ResourceKind::Texture => "texture",
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidHandle(kind) => {
+                write!(f, "The {} handle is invalid or has been freed", kind)
+            }
+            Error::NotDynamic(kind) => write!(
+                f,
+                "Attempted to update a {} which was not created as dynamic",
+                kind
+            ),
+            Error::SizeMismatch {
+                kind,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected {} bytes of data for {}, got {}",
+                expected, kind, actual
+            ),
+            Error::OutOfBounds { kind, end, size } => write!(
+                f,
+                "Update ends at byte {}, past the end of the {} ({} bytes)",
+                end, kind, size
+            ),
+            Error::BuiltIn(kind) => write!(f, "Built-in {}s cannot be freed", kind),
+            Error::InvalidSlot { kind, slot, slots } => write!(
+                f,
+                "There is no {} slot {}; only {} slots exist",
+                kind, slot, slots
+            ),
+            Error::UniformsTooLarge { size, max } => write!(
+                f,
+                "Uniform data is {} bytes, but at most {} bytes are supported",
+                size, max
+            ),
+            Error::MissingVertices => {
+                write!(f, "Draws without a vertex buffer must specify a shader")
+            }
+            Error::BufferTooLarge { kind, size, max } => write!(
+                f,
+                "A {} of {} bytes is larger than the device limit of {} bytes",
+                kind, size, max
+            ),
+            Error::InvalidTextureSize { width, height, max } => write!(
+                f,
+                "Invalid texture size {}x{}; dimensions must be between 1 and {} pixels",
+                width, height, max
+            ),
+            Error::Unsupported(feature) => {
+                write!(f, "The {} device feature is not enabled", feature)
+            }
+            Error::InvalidMsaaSamples(samples) => write!(
+                f,
+                "Invalid MSAA sample count {}; must be 1, 2, 4, 8 or 16",
+                samples
+            ),
+            Error::IndexTypeMismatch { expected, actual } => write!(
+                f,
+                "Expected indices of type {}, got indices of type {}",
+                expected, actual
+            ),
+            Error::LayoutMismatch { expected, actual } => write!(
+                f,
+                "Expected vertices of type {}, got vertices of type {}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
@@ -0,0 +1,53 @@
+use anyhow::{format_err, Context, Result};
+use shaderc::{CompileOptions, Compiler, IncludeType, ResolvedInclude, ShaderKind};
+use std::path::Path;
+
+/// Name of the built-in header, which declares the per-frame UBO, model matrices and push
+/// constants
+pub const BUILTIN_HEADER_NAME: &str = "idek.glsl";
+
+/// Contents of the built-in header
+pub static BUILTIN_HEADER: &str = include_str!("shaders/idek.glsl");
+
+/// Compile GLSL source to SPIR-V. `file_name` is used in error messages, and relative includes
+/// are resolved against it. `#include <idek.glsl>` resolves to the built-in header.
+pub fn compile_glsl(source: &str, kind: ShaderKind, file_name: &str) -> Result<Vec<u8>> {
+    let mut compiler = Compiler::new().context("Failed to initialize shaderc")?;
+    let mut options = CompileOptions::new().context("Failed to initialize shaderc options")?;
+    options.set_include_callback(resolve_include);
+
+    let artifact = compiler
+        .compile_into_spirv(source, kind, file_name, "main", Some(&options))
+        .map_err(|e| format_err!("Failed to compile {}: {}", file_name, e))?;
+
+    Ok(artifact.as_binary_u8().to_vec())
+}
+
+/// Resolve `#include` directives; the built-in header for `<idek.glsl>`, otherwise files on disk
+/// relative to the including file
+fn resolve_include(
+    requested: &str,
+    include_type: IncludeType,
+    requesting: &str,
+    _depth: usize,
+) -> shaderc::IncludeCallbackResult {
+    if include_type == IncludeType::Standard && requested == BUILTIN_HEADER_NAME {
+        return Ok(ResolvedInclude {
+            resolved_name: format!("<{}>", BUILTIN_HEADER_NAME),
+            content: BUILTIN_HEADER.to_string(),
+        });
+    }
+
+    let path = match Path::new(requesting).parent() {
+        Some(dir) if include_type == IncludeType::Relative => dir.join(requested),
+        _ => requested.into(),
+    };
+
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to include {}: {}", path.display(), e))?;
+
+    Ok(ResolvedInclude {
+        resolved_name: path.display().to_string(),
+        content,
+    })
+}
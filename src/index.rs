@@ -0,0 +1,34 @@
+use watertender::vk;
+
+/// Integer types which may be used in index buffers: `u16` and `u32`. 16-bit indices use half
+/// the memory, but can only address 65536 vertices (see `DrawCmd::base_vertex`).
+pub trait Index: bytemuck::Pod + sealed::Sealed {
+    #[doc(hidden)]
+    fn index_type() -> vk::IndexType;
+}
+
+impl Index for u16 {
+    fn index_type() -> vk::IndexType {
+        vk::IndexType::UINT16
+    }
+}
+
+impl Index for u32 {
+    fn index_type() -> vk::IndexType {
+        vk::IndexType::UINT32
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+}
+
+/// Name of an index type, for error messages
+pub(crate) fn index_type_name(index_type: vk::IndexType) -> &'static str {
+    match index_type {
+        vk::IndexType::UINT16 => "u16",
+        _ => "u32",
+    }
+}
@@ -0,0 +1,32 @@
+use crate::engine::TRANSFORM_IDENTITY;
+use crate::Transform;
+
+/// Per-instance data, read by `DEFAULT_INSTANCED_VERTEX_SHADER`
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Instance {
+    /// Transform applied to each vertex of this instance, after the DrawCmd's transform
+    pub transform: Transform,
+    /// Multiplied with the color of each vertex of this instance
+    pub color: [f32; 3],
+}
+
+unsafe impl bytemuck::Zeroable for Instance {}
+unsafe impl bytemuck::Pod for Instance {}
+
+impl Instance {
+    pub fn new(transform: Transform, color: [f32; 3]) -> Self {
+        Self { transform, color }
+    }
+
+    /// An instance with the given transform, and no change in color
+    pub fn from_transform(transform: Transform) -> Self {
+        Self::new(transform, [1.; 3])
+    }
+}
+
+impl Default for Instance {
+    fn default() -> Self {
+        Self::from_transform(TRANSFORM_IDENTITY)
+    }
+}
@@ -1,8 +1,35 @@
 use anyhow::Result;
+mod background;
+mod buffer_sets;
+mod device_info;
 mod draw_cmd;
 mod engine;
-pub use draw_cmd::DrawCmd;
+mod error;
+#[cfg(feature = "shaderc")]
+mod glsl;
+mod index;
+mod instance;
+mod mesh;
+mod pipeline;
+mod sampler;
+mod shader_files;
+mod shader_settings;
+mod vertex_layout;
+mod window_title;
+pub use background::Background;
+pub use buffer_sets::{BufferBindings, STORAGE_BUFFER_SLOTS, UNIFORM_BUFFER_SLOTS};
+pub use device_info::{DeviceFeatures, DeviceInfo, DeviceLimits, DeviceType};
+pub use draw_cmd::{DrawCmd, Uniforms, MAX_UNIFORMS_SIZE};
 pub use engine::launch;
+pub use error::{DrawError, Error, ResourceKind};
+#[cfg(feature = "shaderc")]
+pub use glsl::{BUILTIN_HEADER, BUILTIN_HEADER_NAME};
+pub use index::Index;
+pub use instance::Instance;
+pub use mesh::{Mesh, MeshBuilder, SubMesh};
+pub use sampler::{AddressMode, Filter, SamplerSettings};
+pub use shader_settings::{BlendMode, CullMode, FrontFace, PolygonMode, ShaderSettings};
+pub use vertex_layout::{AttributeFormat, VertexAttribute, VertexLayout};
 pub use watertender::mainloop::{Platform, PlatformEvent as Event};
 use watertender::nalgebra::{Matrix4, Vector4};
 pub use watertender::vertex::Vertex;
@@ -22,13 +49,25 @@ pub use watertender::openxr;
 pub use watertender::winit;
 
 pub static DEFAULT_VERTEX_SHADER: &[u8] = include_bytes!("shaders/unlit.vert.spv");
+/// Same as `DEFAULT_VERTEX_SHADER`, but also applies the per-instance transform and color
+pub static DEFAULT_INSTANCED_VERTEX_SHADER: &[u8] =
+    include_bytes!("shaders/unlit_instanced.vert.spv");
 pub static DEFAULT_FRAGMENT_SHADER: &[u8] = include_bytes!("shaders/unlit.frag.spv");
+/// Covers the screen with a single triangle, without a vertex buffer. Outputs UV coordinates at
+/// location 0. Used by `Context::fullscreen_shader`
+pub static FULLSCREEN_VERTEX_SHADER: &[u8] = include_bytes!("shaders/fullscreen.vert.spv");
+/// Blends vertically between two colors, for use with `FULLSCREEN_VERTEX_SHADER`. Used for
+/// `Background::Gradient`
+pub static GRADIENT_FRAGMENT_SHADER: &[u8] = include_bytes!("shaders/gradient.frag.spv");
+/// Samples the texture bound to the draw, using the red and green vertex color channels as UV
+pub static DEFAULT_TEXTURED_FRAGMENT_SHADER: &[u8] = include_bytes!("shaders/unlit_tex.frag.spv");
 
 /// Commonly used items
 pub mod prelude {
     pub use super::{
-        launch, App, Context, DrawCmd, Event, IndexBuffer, MultiPlatformCamera, Platform,
-        Primitive, Settings, Shader, Vertex, VertexBuffer, DEFAULT_FRAGMENT_SHADER,
+        launch, App, Context, DrawCmd, Event, IndexBuffer, Instance, InstanceBuffer,
+        MultiPlatformCamera, Platform, Primitive, Settings, Shader, Texture, Vertex, VertexBuffer,
+        DEFAULT_FRAGMENT_SHADER, DEFAULT_INSTANCED_VERTEX_SHADER, DEFAULT_TEXTURED_FRAGMENT_SHADER,
         DEFAULT_VERTEX_SHADER,
     };
     pub use anyhow::Result;
@@ -36,16 +75,39 @@ pub mod prelude {
 
 /// Launch settings
 pub struct Settings<Args = ()> {
-    /// MSAA samples. Must be a power of two (up to 16)
+    /// MSAA samples. Must be a power of two (up to 16). If the device does not support this many,
+    /// the highest supported count below it is used instead (see `Context::msaa_samples`)
     pub msaa_samples: u8,
 
     /// If true, use OpenXR to display in VR
     pub vr: bool,
 
-    /// Application name
+    /// Application name, also used as the window title
     pub name: String,
 
-    /// Maximum number of transforms able to be used at once
+    /// If true, append the frame rate and frame time to the window title
+    pub show_fps: bool,
+
+    /// Initial inner size of the window in pixels, if not the platform default
+    pub window_size: Option<(u32, u32)>,
+
+    /// Initial position of the window in pixels, if not the platform default
+    pub window_position: Option<(i32, i32)>,
+
+    /// If true, start in borderless fullscreen on the current monitor
+    pub fullscreen: bool,
+
+    /// Whether the user may resize the window
+    pub resizable: bool,
+
+    /// Drawn behind everything else each frame
+    pub background: Background,
+
+    /// If true, enable the Vulkan validation layers. Defaults to true in debug builds
+    pub validation_layers: bool,
+
+    /// Initial capacity of the per-frame transform buffers. They grow on demand, so this is only a
+    /// hint to avoid reallocation
     pub max_transforms: usize,
 
     /// User-defined arguments
@@ -58,6 +120,13 @@ impl<T: Default> Default for Settings<T> {
             msaa_samples: 4,
             vr: false,
             name: "Idek".to_string(),
+            show_fps: false,
+            window_size: None,
+            window_position: None,
+            fullscreen: false,
+            resizable: true,
+            background: Background::default(),
+            validation_layers: cfg!(debug_assertions),
             max_transforms: 10_000,
             args: Default::default(),
         }
@@ -77,6 +146,63 @@ impl<Args> Settings<Args> {
         self
     }
 
+    /// Optionally show the frame rate and frame time in the window title
+    pub fn show_fps(mut self, show_fps: bool) -> Self {
+        self.show_fps = show_fps;
+        self
+    }
+
+    /// Set the initial inner size of the window in pixels
+    pub fn window_size(mut self, width: u32, height: u32) -> Self {
+        self.window_size = Some((width, height));
+        self
+    }
+
+    /// Set the initial position of the window in pixels
+    pub fn window_position(mut self, x: i32, y: i32) -> Self {
+        self.window_position = Some((x, y));
+        self
+    }
+
+    /// Optionally start in borderless fullscreen
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    /// Set whether the user may resize the window
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Set the color the screen is cleared to each frame
+    pub fn clear_color(mut self, color: [f32; 4]) -> Self {
+        self.background = Background::Color(color);
+        self
+    }
+
+    /// Set the background drawn each frame
+    pub fn background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Enable or disable the Vulkan validation layers
+    pub fn validation_layers(mut self, validation_layers: bool) -> Self {
+        self.validation_layers = validation_layers;
+        self
+    }
+
+    /// Check the settings for mistakes which would otherwise surface as Vulkan errors. Called by
+    /// `launch`; limits of the device are checked once it has been chosen.
+    pub fn validate(&self) -> Result<()> {
+        if !matches!(self.msaa_samples, 1 | 2 | 4 | 8 | 16) {
+            return Err(Error::InvalidMsaaSamples(self.msaa_samples).into());
+        }
+        Ok(())
+    }
+
     /// Enable VR if there are any command line arguments. Useful for debugging or simple use-cases
     pub fn vr_if_any_args(mut self) -> Self {
         self.vr = std::env::args().skip(1).next().is_some();
@@ -89,7 +215,7 @@ impl<Args> Settings<Args> {
         self
     }
 
-    /// Enable VR if there are any command line arguments
+    /// Set the initial capacity of the transform buffers
     pub fn max_transforms(mut self, max_transforms: usize) -> Self {
         self.max_transforms = max_transforms;
         self
@@ -107,7 +233,8 @@ pub trait App<Args = ()>: Sized {
     /// Initialization function, called once to construct the app
     fn init(ctx: &mut Context, platform: &mut Platform, args: Args) -> Result<Self>;
 
-    /// Called once per frame. Most app logic should live here.
+    /// Called once per frame. Most app logic should live here. Invalid draws are skipped rather
+    /// than ending the app; see `Context::last_frame_errors`.
     fn frame(&mut self, ctx: &mut Context, platform: &mut Platform) -> Result<Vec<DrawCmd>>;
 
     /// Called once per event
@@ -142,8 +269,12 @@ slotmap::new_key_type! {
     pub struct Shader;
     /// A texture
     pub struct Texture;
-    // /// An instance buffer
-    // pub struct InstanceBuffer;
+    /// An instance buffer
+    pub struct InstanceBuffer;
+    /// A storage buffer for use in custom shaders
+    pub struct StorageBuffer;
+    /// A uniform buffer for use in custom shaders
+    pub struct UniformBuffer;
 }
 
 /// Context with which to change the rendering environment from within an App
@@ -0,0 +1,76 @@
+use crate::{Context, DrawCmd, IndexBuffer, VertexBuffer, VertexLayout};
+use anyhow::Result;
+
+/// A part of a vertex and index buffer pair, drawn with `DrawCmd::submesh`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SubMesh {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// Added to each index before reading a vertex
+    pub base_vertex: i32,
+}
+
+/// A vertex and index buffer pair shared by many sub-meshes
+#[derive(Clone, Debug)]
+pub struct Mesh {
+    pub vertices: VertexBuffer,
+    pub indices: IndexBuffer,
+    pub submeshes: Vec<SubMesh>,
+}
+
+impl Mesh {
+    /// Draw the sub-mesh at the given index
+    ///
+    /// # Panics
+    /// If there is no sub-mesh at `submesh`
+    pub fn draw(&self, submesh: usize) -> DrawCmd {
+        DrawCmd::new(self.vertices)
+            .indices(self.indices)
+            .submesh(self.submeshes[submesh])
+    }
+}
+
+/// Packs many meshes into a single vertex and index buffer. Indices of each mesh are relative
+/// to its own vertices.
+pub struct MeshBuilder<V> {
+    vertices: Vec<V>,
+    indices: Vec<u32>,
+    submeshes: Vec<SubMesh>,
+}
+
+impl<V: VertexLayout> Default for MeshBuilder<V> {
+    fn default() -> Self {
+        Self {
+            vertices: vec![],
+            indices: vec![],
+            submeshes: vec![],
+        }
+    }
+}
+
+impl<V: VertexLayout> MeshBuilder<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a mesh, returning the index of its sub-mesh
+    pub fn push(&mut self, vertices: &[V], indices: &[u32]) -> usize {
+        self.submeshes.push(SubMesh {
+            first_index: self.indices.len() as u32,
+            index_count: indices.len() as u32,
+            base_vertex: self.vertices.len() as i32,
+        });
+        self.vertices.extend_from_slice(vertices);
+        self.indices.extend_from_slice(indices);
+        self.submeshes.len() - 1
+    }
+
+    /// Upload all meshes added so far
+    pub fn upload(&self, ctx: &mut Context, dynamic: bool) -> Result<Mesh> {
+        Ok(Mesh {
+            vertices: ctx.vertices_of(&self.vertices, dynamic)?,
+            indices: ctx.indices(&self.indices, dynamic)?,
+            submeshes: self.submeshes.clone(),
+        })
+    }
+}
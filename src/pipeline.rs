@@ -0,0 +1,202 @@
+use crate::vertex_layout::VertexLayoutInfo;
+use crate::{Instance, ShaderSettings};
+use anyhow::{ensure, Result};
+use std::ffi::CString;
+use watertender::prelude::*;
+
+/// Vertex input binding for per-vertex data
+pub const VERTEX_BINDING: u32 = 0;
+
+/// Vertex input binding for per-instance data
+pub const INSTANCE_BINDING: u32 = 1;
+
+/// State shared by every pipeline of an engine
+#[derive(Copy, Clone)]
+pub struct PipelineTarget {
+    pub render_pass: vk::RenderPass,
+    pub pipeline_layout: vk::PipelineLayout,
+    pub msaa_samples: vk::SampleCountFlagBits,
+}
+
+/// Shaders and state of a single pipeline
+pub struct PipelineDesc<'a> {
+    /// SPIR-V of the vertex shader
+    pub vertex: &'a [u8],
+    /// SPIR-V of the fragment shader
+    pub fragment: &'a [u8],
+    pub primitive: vk::PrimitiveTopology,
+    pub layout: &'a VertexLayoutInfo,
+    pub settings: &'a ShaderSettings,
+}
+
+/// Create a graphics pipeline compatible with the engine's render pass and pipeline layout.
+/// Every pipeline accepts per-vertex data described by `desc.layout`, and per-instance
+/// `Instance` data; shaders which do not read instance attributes may be drawn without an
+/// instance buffer.
+pub fn create_pipeline(
+    core: &Core,
+    target: &PipelineTarget,
+    desc: &PipelineDesc,
+) -> Result<vk::Pipeline> {
+    let (layout, settings) = (desc.layout, desc.settings);
+    let vertex = shader_module(core, desc.vertex)?;
+    let fragment = shader_module(core, desc.fragment)?;
+
+    let binding_descriptions = [
+        vk::VertexInputBindingDescriptionBuilder::new()
+            .binding(VERTEX_BINDING)
+            .stride(layout.stride)
+            .input_rate(vk::VertexInputRate::VERTEX),
+        vk::VertexInputBindingDescriptionBuilder::new()
+            .binding(INSTANCE_BINDING)
+            .stride(std::mem::size_of::<Instance>() as u32)
+            .input_rate(vk::VertexInputRate::INSTANCE),
+    ];
+
+    let attribute_descriptions = vertex_attributes(layout);
+
+    let vertex_input = vk::PipelineVertexInputStateCreateInfoBuilder::new()
+        .vertex_attribute_descriptions(&attribute_descriptions)
+        .vertex_binding_descriptions(&binding_descriptions);
+
+    let input_assembly = vk::PipelineInputAssemblyStateCreateInfoBuilder::new()
+        .topology(desc.primitive)
+        .primitive_restart_enable(false);
+
+    let viewport_state = vk::PipelineViewportStateCreateInfoBuilder::new()
+        .viewport_count(1)
+        .scissor_count(1);
+
+    let dynamic_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+    let dynamic_state =
+        vk::PipelineDynamicStateCreateInfoBuilder::new().dynamic_states(&dynamic_states);
+
+    let rasterizer = vk::PipelineRasterizationStateCreateInfoBuilder::new()
+        .depth_clamp_enable(false)
+        .rasterizer_discard_enable(false)
+        .polygon_mode(settings.polygon_mode.into())
+        .line_width(1.0)
+        .cull_mode(settings.cull_mode.into())
+        .front_face(settings.front_face.into())
+        .depth_bias_enable(false);
+
+    let multisampling = vk::PipelineMultisampleStateCreateInfoBuilder::new()
+        .sample_shading_enable(false)
+        .rasterization_samples(target.msaa_samples);
+
+    let color_blend_attachments = [settings.color_blend_attachment()];
+
+    let color_blending = vk::PipelineColorBlendStateCreateInfoBuilder::new()
+        .logic_op_enable(false)
+        .attachments(&color_blend_attachments);
+
+    let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfoBuilder::new()
+        .depth_test_enable(settings.depth_test)
+        .depth_write_enable(settings.depth_write)
+        .depth_compare_op(vk::CompareOp::LESS)
+        .depth_bounds_test_enable(false)
+        .stencil_test_enable(false);
+
+    let entry_point = CString::new("main")?;
+    let shader_stages = [
+        vk::PipelineShaderStageCreateInfoBuilder::new()
+            .stage(vk::ShaderStageFlagBits::VERTEX)
+            .module(vertex)
+            .name(&entry_point),
+        vk::PipelineShaderStageCreateInfoBuilder::new()
+            .stage(vk::ShaderStageFlagBits::FRAGMENT)
+            .module(fragment)
+            .name(&entry_point),
+    ];
+
+    let create_info = vk::GraphicsPipelineCreateInfoBuilder::new()
+        .stages(&shader_stages)
+        .vertex_input_state(&vertex_input)
+        .input_assembly_state(&input_assembly)
+        .viewport_state(&viewport_state)
+        .rasterization_state(&rasterizer)
+        .multisample_state(&multisampling)
+        .depth_stencil_state(&depth_stencil_state)
+        .color_blend_state(&color_blending)
+        .dynamic_state(&dynamic_state)
+        .layout(target.pipeline_layout)
+        .render_pass(target.render_pass)
+        .subpass(0);
+
+    let pipeline = unsafe {
+        core.device
+            .create_graphics_pipelines(None, &[create_info], None)
+    }
+    .result();
+
+    unsafe {
+        core.device.destroy_shader_module(Some(vertex), None);
+        core.device.destroy_shader_module(Some(fragment), None);
+    }
+
+    Ok(pipeline?[0])
+}
+
+/// Vertex attributes for the vertex type, followed by those of `Instance`
+fn vertex_attributes(
+    layout: &VertexLayoutInfo,
+) -> Vec<vk::VertexInputAttributeDescriptionBuilder<'static>> {
+    let attribute = |binding: u32, location: u32, format: vk::Format, offset: usize| {
+        vk::VertexInputAttributeDescriptionBuilder::new()
+            .binding(binding)
+            .location(location)
+            .format(format)
+            .offset(offset as u32)
+    };
+
+    let vec3 = vk::Format::R32G32B32_SFLOAT;
+    let vec4 = vk::Format::R32G32B32A32_SFLOAT;
+    let column_size = std::mem::size_of::<[f32; 4]>();
+    let transform_size = std::mem::size_of::<crate::Transform>();
+
+    let mut attributes: Vec<_> = layout
+        .attributes
+        .iter()
+        .map(|attr| {
+            attribute(
+                VERTEX_BINDING,
+                attr.location,
+                attr.format.into(),
+                attr.offset as usize,
+            )
+        })
+        .collect();
+
+    // A mat4 attribute occupies one location per column
+    let instance_location = layout.instance_location();
+    for column in 0..4 {
+        attributes.push(attribute(
+            INSTANCE_BINDING,
+            instance_location + column as u32,
+            vec4,
+            column * column_size,
+        ));
+    }
+    attributes.push(attribute(
+        INSTANCE_BINDING,
+        instance_location + 4,
+        vec3,
+        transform_size,
+    ));
+
+    attributes
+}
+
+/// Create a shader module from SPIR-V bytes
+fn shader_module(core: &Core, spirv: &[u8]) -> Result<vk::ShaderModule> {
+    ensure!(
+        spirv.len() % 4 == 0,
+        "SPIR-V length must be a multiple of 4 bytes"
+    );
+    let code: Vec<u32> = spirv
+        .chunks_exact(4)
+        .map(|word| u32::from_le_bytes([word[0], word[1], word[2], word[3]]))
+        .collect();
+    let create_info = vk::ShaderModuleCreateInfoBuilder::new().code(&code);
+    Ok(unsafe { core.device.create_shader_module(&create_info, None, None) }.result()?)
+}
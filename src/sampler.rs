@@ -0,0 +1,88 @@
+use watertender::vk;
+
+/// Texture filtering mode
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// Use the nearest texel (pixelated look)
+    Nearest,
+    /// Linearly interpolate between texels
+    Linear,
+}
+
+/// Behaviour of texture coordinates outside of the range [0, 1]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AddressMode {
+    /// Tile the texture
+    Repeat,
+    /// Tile the texture, flipping every other tile
+    MirroredRepeat,
+    /// Use the texel at the nearest edge
+    ClampToEdge,
+}
+
+/// Texture sampling settings
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SamplerSettings {
+    /// Filter used when the texture is magnified
+    pub mag_filter: Filter,
+    /// Filter used when the texture is minified
+    pub min_filter: Filter,
+    /// Address mode for both the U and V axes
+    pub address_mode: AddressMode,
+}
+
+impl Default for SamplerSettings {
+    fn default() -> Self {
+        Self {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            address_mode: AddressMode::Repeat,
+        }
+    }
+}
+
+impl SamplerSettings {
+    /// Set both the minification and magnification filter
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.mag_filter = filter;
+        self.min_filter = filter;
+        self
+    }
+
+    /// Set the magnification filter
+    pub fn mag_filter(mut self, filter: Filter) -> Self {
+        self.mag_filter = filter;
+        self
+    }
+
+    /// Set the minification filter
+    pub fn min_filter(mut self, filter: Filter) -> Self {
+        self.min_filter = filter;
+        self
+    }
+
+    /// Set the address mode
+    pub fn address_mode(mut self, address_mode: AddressMode) -> Self {
+        self.address_mode = address_mode;
+        self
+    }
+}
+
+impl From<Filter> for vk::Filter {
+    fn from(filter: Filter) -> Self {
+        match filter {
+            Filter::Nearest => vk::Filter::NEAREST,
+            Filter::Linear => vk::Filter::LINEAR,
+        }
+    }
+}
+
+impl From<AddressMode> for vk::SamplerAddressMode {
+    fn from(mode: AddressMode) -> Self {
+        match mode {
+            AddressMode::Repeat => vk::SamplerAddressMode::REPEAT,
+            AddressMode::MirroredRepeat => vk::SamplerAddressMode::MIRRORED_REPEAT,
+            AddressMode::ClampToEdge => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        }
+    }
+}
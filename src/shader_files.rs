@@ -0,0 +1,137 @@
+use anyhow::Result;
+use std::path::Path;
+
+/// Shader stage of a file on disk
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Vertex,
+    Fragment,
+}
+
+/// Load a shader from disk. Files ending in `.spv` are read as SPIR-V, anything else is compiled
+/// as GLSL, which requires the `shaderc` feature.
+pub fn load_shader_file(path: &Path, stage: Stage) -> Result<Vec<u8>> {
+    if path.extension().map_or(false, |ext| ext == "spv") {
+        return std::fs::read(path)
+            .map_err(|e| anyhow::format_err!("Failed to read {}: {}", path.display(), e));
+    }
+
+    compile_shader_file(path, stage)
+}
+
+#[cfg(feature = "shaderc")]
+fn compile_shader_file(path: &Path, stage: Stage) -> Result<Vec<u8>> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::format_err!("Failed to read {}: {}", path.display(), e))?;
+    let kind = match stage {
+        Stage::Vertex => shaderc::ShaderKind::Vertex,
+        Stage::Fragment => shaderc::ShaderKind::Fragment,
+    };
+    crate::glsl::compile_glsl(&source, kind, &path.display().to_string())
+}
+
+#[cfg(not(feature = "shaderc"))]
+fn compile_shader_file(path: &Path, _stage: Stage) -> Result<Vec<u8>> {
+    anyhow::bail!(
+        "Cannot compile {}; GLSL shaders require the shaderc feature",
+        path.display()
+    )
+}
+
+#[cfg(feature = "hot-reload")]
+pub use watcher::{ShaderWatcher, TrackedShader};
+
+#[cfg(feature = "hot-reload")]
+mod watcher {
+    use crate::{Shader, ShaderSettings};
+    use anyhow::Result;
+    use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+    use std::sync::mpsc::{channel, Receiver};
+    use watertender::vk;
+
+    /// Everything needed to rebuild a shader from its files
+    #[derive(Clone)]
+    pub struct TrackedShader {
+        pub vertex: PathBuf,
+        pub fragment: PathBuf,
+        pub primitive: vk::PrimitiveTopology,
+        pub settings: ShaderSettings,
+    }
+
+    /// Watches the files of shaders created with `shader_from_files`
+    pub struct ShaderWatcher {
+        watcher: RecommendedWatcher,
+        events: Receiver<notify::Result<Event>>,
+        shaders: HashMap<Shader, TrackedShader>,
+        watched_dirs: HashSet<PathBuf>,
+    }
+
+    impl ShaderWatcher {
+        pub fn new() -> Result<Self> {
+            let (tx, events) = channel();
+            let watcher = notify::recommended_watcher(tx)?;
+            Ok(Self {
+                watcher,
+                events,
+                shaders: HashMap::new(),
+                watched_dirs: HashSet::new(),
+            })
+        }
+
+        /// Start watching the files of the given shader. The files must exist.
+        pub fn track(&mut self, shader: Shader, mut tracked: TrackedShader) -> Result<()> {
+            tracked.vertex = tracked.vertex.canonicalize()?;
+            tracked.fragment = tracked.fragment.canonicalize()?;
+
+            // Watch directories rather than files, as editors often save by replacing the file
+            for path in [&tracked.vertex, &tracked.fragment].iter() {
+                if let Some(dir) = path.parent() {
+                    if self.watched_dirs.insert(dir.to_path_buf()) {
+                        self.watcher.watch(dir, RecursiveMode::NonRecursive)?;
+                    }
+                }
+            }
+
+            self.shaders.insert(shader, tracked);
+            Ok(())
+        }
+
+        /// Stop watching the files of the given shader
+        pub fn untrack(&mut self, shader: Shader) {
+            self.shaders.remove(&shader);
+        }
+
+        /// Returns the shaders with files which have changed since the last call. Errors reported
+        /// by the watcher are appended to `errors`.
+        pub fn changed(&mut self, errors: &mut Vec<anyhow::Error>) -> Vec<(Shader, TrackedShader)> {
+            let mut changed_paths = HashSet::new();
+            for event in self.events.try_iter() {
+                match event {
+                    Ok(event) => {
+                        if !matches!(event.kind, EventKind::Access(_)) {
+                            changed_paths.extend(event.paths);
+                        }
+                    }
+                    Err(e) => {
+                        errors.push(anyhow::Error::new(e).context("Shader file watcher error"))
+                    }
+                }
+            }
+
+            if changed_paths.is_empty() {
+                return vec![];
+            }
+
+            self.shaders
+                .iter()
+                .filter(|(_, tracked)| {
+                    changed_paths.contains(&tracked.vertex)
+                        || changed_paths.contains(&tracked.fragment)
+                })
+                .map(|(&shader, tracked)| (shader, tracked.clone()))
+                .collect()
+        }
+    }
+}
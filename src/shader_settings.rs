@@ -0,0 +1,174 @@
+use watertender::vk;
+
+/// How fragment colors are combined with the colors already in the framebuffer
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Replace the existing color
+    Opaque,
+    /// Standard alpha blending: `src * src_alpha + dst * (1 - src_alpha)`
+    Alpha,
+    /// Add to the existing color, weighted by alpha: `src * src_alpha + dst`
+    Additive,
+    /// Alpha blending for colors which are already multiplied by alpha:
+    /// `src + dst * (1 - src_alpha)`
+    Premultiplied,
+}
+
+/// Which faces of triangles are discarded
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CullMode {
+    /// Draw both faces
+    None,
+    Front,
+    Back,
+}
+
+/// Winding order of front-facing triangles
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrontFace {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// How polygons are rasterized. Modes other than `Fill` require the `fillModeNonSolid` device
+/// feature, which idek cannot enable yet; creating a shader with them returns
+/// `Error::Unsupported`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PolygonMode {
+    Fill,
+    Line,
+    Point,
+}
+
+/// Fixed-function pipeline state for a shader
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ShaderSettings {
+    pub blend: BlendMode,
+    /// Discard fragments which are behind existing geometry
+    pub depth_test: bool,
+    /// Write the depth of fragments to the depth buffer
+    pub depth_write: bool,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    pub polygon_mode: PolygonMode,
+}
+
+impl Default for ShaderSettings {
+    fn default() -> Self {
+        Self {
+            blend: BlendMode::Opaque,
+            depth_test: true,
+            depth_write: true,
+            cull_mode: CullMode::Back,
+            front_face: FrontFace::Clockwise,
+            polygon_mode: PolygonMode::Fill,
+        }
+    }
+}
+
+impl ShaderSettings {
+    /// Set the blend mode
+    pub fn blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Enable or disable depth testing
+    pub fn depth_test(mut self, depth_test: bool) -> Self {
+        self.depth_test = depth_test;
+        self
+    }
+
+    /// Enable or disable depth writes
+    pub fn depth_write(mut self, depth_write: bool) -> Self {
+        self.depth_write = depth_write;
+        self
+    }
+
+    /// Set the cull mode
+    pub fn cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    /// Set the winding order of front faces
+    pub fn front_face(mut self, front_face: FrontFace) -> Self {
+        self.front_face = front_face;
+        self
+    }
+
+    /// Set the polygon mode
+    pub fn polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
+    /// Color blend state for the single color attachment
+    pub(crate) fn color_blend_attachment(
+        &self,
+    ) -> vk::PipelineColorBlendAttachmentStateBuilder<'static> {
+        use vk::BlendFactor as F;
+
+        let builder = vk::PipelineColorBlendAttachmentStateBuilder::new().color_write_mask(
+            vk::ColorComponentFlags::R
+                | vk::ColorComponentFlags::G
+                | vk::ColorComponentFlags::B
+                | vk::ColorComponentFlags::A,
+        );
+
+        let (src_color, dst_color, src_alpha, dst_alpha) = match self.blend {
+            BlendMode::Opaque => return builder.blend_enable(false),
+            BlendMode::Alpha => (
+                F::SRC_ALPHA,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+            ),
+            BlendMode::Additive => (F::SRC_ALPHA, F::ONE, F::ONE, F::ONE),
+            BlendMode::Premultiplied => (
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+                F::ONE,
+                F::ONE_MINUS_SRC_ALPHA,
+            ),
+        };
+
+        builder
+            .blend_enable(true)
+            .src_color_blend_factor(src_color)
+            .dst_color_blend_factor(dst_color)
+            .color_blend_op(vk::BlendOp::ADD)
+            .src_alpha_blend_factor(src_alpha)
+            .dst_alpha_blend_factor(dst_alpha)
+            .alpha_blend_op(vk::BlendOp::ADD)
+    }
+}
+
+impl From<CullMode> for vk::CullModeFlags {
+    fn from(mode: CullMode) -> Self {
+        match mode {
+            CullMode::None => vk::CullModeFlags::NONE,
+            CullMode::Front => vk::CullModeFlags::FRONT,
+            CullMode::Back => vk::CullModeFlags::BACK,
+        }
+    }
+}
+
+impl From<FrontFace> for vk::FrontFace {
+    fn from(face: FrontFace) -> Self {
+        match face {
+            FrontFace::Clockwise => vk::FrontFace::CLOCKWISE,
+            FrontFace::CounterClockwise => vk::FrontFace::COUNTER_CLOCKWISE,
+        }
+    }
+}
+
+impl From<PolygonMode> for vk::PolygonMode {
+    fn from(mode: PolygonMode) -> Self {
+        match mode {
+            PolygonMode::Fill => vk::PolygonMode::FILL,
+            PolygonMode::Line => vk::PolygonMode::LINE,
+            PolygonMode::Point => vk::PolygonMode::POINT,
+        }
+    }
+}
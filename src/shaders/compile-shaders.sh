@@ -7,3 +7,6 @@ compile() {
 compile unlit.vert
 compile unlit.frag
 compile unlit_tex.frag
+compile unlit_instanced.vert
+compile fullscreen.vert
+compile gradient.frag
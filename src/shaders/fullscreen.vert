@@ -0,0 +1,13 @@
+#version 450
+
+// A single triangle covering the screen, generated from the vertex index. Draw three vertices
+// without a vertex buffer.
+
+// Fragment outputs. (0, 0) is the top-left of the screen, (1, 1) the bottom-right
+layout(location = 0) out vec2 frag_uv;
+
+void main() {
+    vec2 uv = vec2(float((gl_VertexIndex << 1) & 2), float(gl_VertexIndex & 2));
+    frag_uv = uv;
+    gl_Position = vec4(uv * 2.0 - 1.0, 0.0, 1.0);
+}
@@ -0,0 +1,14 @@
+#version 450
+
+// Vertical gradient background, drawn with fullscreen.vert before all other draws
+
+// Colors at the top and bottom of the screen
+#define IDEK_UNIFORMS vec4 top; vec4 bottom;
+#include "idek.glsl"
+
+layout(location = 0) in vec2 frag_uv;
+layout(location = 0) out vec4 out_color;
+
+void main() {
+    out_color = mix(top, bottom, frag_uv.y);
+}
@@ -0,0 +1,45 @@
+// Built-in declarations for idek shaders. Include with `#include <idek.glsl>`
+#extension GL_EXT_multiview : require
+
+// Per-frame UBO. Arrays have one element per view (eye); use gl_ViewIndex
+layout(binding = 0) uniform PerFrame {
+    // Combined camera transform (projection * view)
+    mat4 camera[2];
+    // Seconds since the engine started
+    float time;
+    // View and projection matrices. In VR these are not reported separately, so `view` holds
+    // the combined transform and `projection` is the identity
+    mat4 view[2];
+    mat4 projection[2];
+    mat4 inverse_view[2];
+    mat4 inverse_projection[2];
+    // Viewport size in pixels
+    vec2 resolution;
+    // Seconds since the previous frame
+    float delta_time;
+    // Number of frames rendered before this one, starting at 0
+    uint frame_index;
+    // Cursor position in pixels, from the top-left of the window
+    vec2 cursor;
+};
+
+// Model matrices
+layout(binding = 1) buffer Models {
+    mat4 model_mats[];
+};
+
+// Resource indices, followed by per-draw uniforms (see `DrawCmd::uniforms`). Declare the
+// uniform members by defining IDEK_UNIFORMS before including this file, for example:
+// #define IDEK_UNIFORMS vec4 tint; float scale;
+// The uniforms start at offset 16, so members of any type are at their natural alignment.
+#ifndef IDEK_UNIFORMS
+#define IDEK_UNIFORMS
+#endif
+
+layout(push_constant) uniform Indices {
+    uint model_index;
+    uint idek_reserved_0;
+    uint idek_reserved_1;
+    uint idek_reserved_2;
+    IDEK_UNIFORMS
+};
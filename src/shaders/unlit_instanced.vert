@@ -0,0 +1,46 @@
+#version 450
+#extension GL_EXT_multiview : require
+
+// Per-frame UBO
+layout(binding = 0) uniform PerFrame {
+    mat4 camera[2];
+    float anim;
+};
+
+// Model matrices
+layout(binding = 1) buffer Models {
+    mat4 model_mats[];
+};
+
+// Resource indices
+layout(push_constant) uniform Indices {
+    uint model_index;
+};
+
+// Vertex data
+layout(location = 0) in vec3 vert_pos;
+layout(location = 1) in vec3 vert_color;
+
+// Instance data (transform columns, then color)
+layout(location = 2) in vec4 inst_transform_0;
+layout(location = 3) in vec4 inst_transform_1;
+layout(location = 4) in vec4 inst_transform_2;
+layout(location = 5) in vec4 inst_transform_3;
+layout(location = 6) in vec3 inst_color;
+
+// Fragment outputs
+layout(location = 0) out vec3 frag_color;
+
+void main() {
+    mat4 inst_transform = mat4(
+        inst_transform_0,
+        inst_transform_1,
+        inst_transform_2,
+        inst_transform_3
+    );
+    gl_Position = camera[gl_ViewIndex]
+        * model_mats[model_index]
+        * inst_transform
+        * vec4(vert_pos, 1.0);
+    frag_color = vert_color * inst_color;
+}
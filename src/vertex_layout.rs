@@ -0,0 +1,144 @@
+use crate::Vertex;
+use std::any::TypeId;
+use watertender::vk;
+
+/// Data type of a single vertex attribute, as seen by the vertex shader
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttributeFormat {
+    /// `float`
+    Float,
+    /// `vec2`
+    Vec2,
+    /// `vec3`
+    Vec3,
+    /// `vec4`
+    Vec4,
+    /// `int`
+    Int,
+    /// `ivec2`
+    IVec2,
+    /// `ivec3`
+    IVec3,
+    /// `ivec4`
+    IVec4,
+    /// `uint`
+    UInt,
+    /// `uvec2`
+    UVec2,
+    /// `uvec3`
+    UVec3,
+    /// `uvec4`
+    UVec4,
+    /// Four bytes, read as a `vec4` in the range 0 to 1. Useful for RGBA colors
+    UNorm8x4,
+}
+
+impl From<AttributeFormat> for vk::Format {
+    fn from(format: AttributeFormat) -> Self {
+        match format {
+            AttributeFormat::Float => vk::Format::R32_SFLOAT,
+            AttributeFormat::Vec2 => vk::Format::R32G32_SFLOAT,
+            AttributeFormat::Vec3 => vk::Format::R32G32B32_SFLOAT,
+            AttributeFormat::Vec4 => vk::Format::R32G32B32A32_SFLOAT,
+            AttributeFormat::Int => vk::Format::R32_SINT,
+            AttributeFormat::IVec2 => vk::Format::R32G32_SINT,
+            AttributeFormat::IVec3 => vk::Format::R32G32B32_SINT,
+            AttributeFormat::IVec4 => vk::Format::R32G32B32A32_SINT,
+            AttributeFormat::UInt => vk::Format::R32_UINT,
+            AttributeFormat::UVec2 => vk::Format::R32G32_UINT,
+            AttributeFormat::UVec3 => vk::Format::R32G32B32_UINT,
+            AttributeFormat::UVec4 => vk::Format::R32G32B32A32_UINT,
+            AttributeFormat::UNorm8x4 => vk::Format::R8G8B8A8_UNORM,
+        }
+    }
+}
+
+/// A single attribute of a vertex type
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct VertexAttribute {
+    /// Shader input location, i.e. `layout(location = N)`
+    pub location: u32,
+    pub format: AttributeFormat,
+    /// Offset of the attribute from the start of the vertex, in bytes
+    pub offset: u32,
+}
+
+impl VertexAttribute {
+    pub fn new(location: u32, format: AttributeFormat, offset: usize) -> Self {
+        Self {
+            location,
+            format,
+            offset: offset as u32,
+        }
+    }
+}
+
+/// Describes the attributes of a vertex type, so that it may be used in vertex buffers and
+/// shaders. Instance attributes (see `Instance`) are placed at the locations directly following
+/// the highest vertex attribute location.
+pub trait VertexLayout: bytemuck::Pod + 'static {
+    /// The attributes of this vertex type
+    fn attributes() -> Vec<VertexAttribute>;
+}
+
+impl VertexLayout for Vertex {
+    fn attributes() -> Vec<VertexAttribute> {
+        vec![
+            VertexAttribute::new(0, AttributeFormat::Vec3, 0),
+            VertexAttribute::new(1, AttributeFormat::Vec3, std::mem::size_of::<[f32; 3]>()),
+        ]
+    }
+}
+
+/// Identifies the vertex type a buffer or shader was created with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct LayoutId {
+    type_id: TypeId,
+    pub name: &'static str,
+}
+
+impl LayoutId {
+    pub fn of<V: 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<V>(),
+            name: std::any::type_name::<V>(),
+        }
+    }
+}
+
+/// Everything needed to build the vertex input state of a pipeline for a vertex type
+#[derive(Clone, Debug)]
+pub(crate) struct VertexLayoutInfo {
+    pub id: LayoutId,
+    pub stride: u32,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+impl VertexLayoutInfo {
+    pub fn of<V: VertexLayout>() -> Self {
+        Self {
+            id: LayoutId::of::<V>(),
+            stride: std::mem::size_of::<V>() as u32,
+            attributes: V::attributes(),
+        }
+    }
+
+    /// No per-vertex data, for shaders which generate geometry from `gl_VertexIndex`. This is
+    /// not a `VertexLayout`, as vertex buffers of a zero-sized type cannot be created.
+    pub fn none() -> Self {
+        Self {
+            id: LayoutId::of::<()>(),
+            stride: 0,
+            attributes: vec![],
+        }
+    }
+
+    /// First location available for instance attributes
+    pub fn instance_location(&self) -> u32 {
+        self.attributes
+            .iter()
+            .map(|attribute| attribute.location + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
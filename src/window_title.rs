@@ -0,0 +1,73 @@
+use crate::Platform;
+use std::time::{Duration, Instant};
+
+/// How often the frame rate suffix is refreshed
+const FPS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Keeps the window title in sync with the title set by the app, optionally followed by the
+/// frame rate and frame time
+pub struct WindowTitle {
+    title: String,
+    show_fps: bool,
+    /// Set when the window must be retitled on the next frame
+    dirty: bool,
+    /// Frames rendered since `since`
+    frames: u32,
+    since: Instant,
+    /// Most recent frame rate suffix
+    suffix: String,
+}
+
+impl WindowTitle {
+    pub fn new(title: String, show_fps: bool) -> Self {
+        Self {
+            title,
+            show_fps,
+            dirty: true,
+            frames: 0,
+            since: Instant::now(),
+            suffix: String::new(),
+        }
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+        self.dirty = true;
+    }
+
+    pub fn set_show_fps(&mut self, show_fps: bool) {
+        self.show_fps = show_fps;
+        self.suffix.clear();
+        self.frames = 0;
+        self.since = Instant::now();
+        self.dirty = true;
+    }
+
+    /// Count a frame, and retitle the window if anything changed. Does nothing in VR.
+    pub fn frame(&mut self, platform: &mut Platform) {
+        if self.show_fps {
+            self.frames += 1;
+            let elapsed = self.since.elapsed();
+            if elapsed >= FPS_INTERVAL {
+                let seconds = elapsed.as_secs_f32();
+                self.suffix = format!(
+                    " - {:.0} FPS ({:.2} ms)",
+                    self.frames as f32 / seconds,
+                    seconds * 1000. / self.frames as f32
+                );
+                self.frames = 0;
+                self.since = Instant::now();
+                self.dirty = true;
+            }
+        }
+
+        if !self.dirty {
+            return;
+        }
+
+        if let Platform::Winit { window, .. } = platform {
+            window.set_title(&format!("{}{}", self.title, self.suffix));
+            self.dirty = false;
+        }
+    }
+}